@@ -1,7 +1,8 @@
+use std::collections::HashSet;
 use std::env;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 // Words that are candidates for answers
 const WORDS_USED_FILENAME: &str = "words-used.txt";
@@ -16,12 +17,7 @@ pub fn main() {
         File::create(PathBuf::from(env::var("OUT_DIR").expect("ENV{OUT_DIR}")).join("words.rs"))
             .expect("File::create");
 
-    let words_used: Vec<String> =
-        BufReader::new(File::open(manifest_dir.join(WORDS_USED_FILENAME)).expect("open"))
-            .lines()
-            .take_while(Result::is_ok)
-            .map(Result::unwrap)
-            .collect();
+    let words_used = read_list(&manifest_dir.join(WORDS_USED_FILENAME));
     write!(
         wordfile,
         "pub const WORDS_USED: &[&str] = &{:?};",
@@ -29,16 +25,97 @@ pub fn main() {
     )
     .expect("write");
 
-    let words_used: Vec<String> =
-        BufReader::new(File::open(manifest_dir.join(WORDS_XTRA_FILENAME)).expect("open"))
-            .lines()
-            .take_while(Result::is_ok)
-            .map(Result::unwrap)
-            .collect();
+    let words_xtra = read_list(&manifest_dir.join(WORDS_XTRA_FILENAME));
     write!(
         wordfile,
         "pub const WORDS_XTRA: &[&str] = &{:?};",
-        words_used
+        words_xtra
     )
     .expect("write");
+
+    let letters: Vec<char> = ('a'..='z').collect();
+    write_frequencies(&mut wordfile, "WORDS_USED", &words_used, &letters);
+    write_frequencies(&mut wordfile, "WORDS_XTRA", &words_xtra, &letters);
+
+    // Every word that can be guessed, sorted so lookups can binary search
+    let mut words_accepted: Vec<&String> = words_used.iter().chain(words_xtra.iter()).collect();
+    words_accepted.sort();
+    words_accepted.dedup();
+    write!(
+        wordfile,
+        "pub const WORDS_ACCEPTED: &[&str] = &{:?};",
+        words_accepted
+    )
+    .expect("write");
+
+    // Answers for the Primel variant are the five-digit primes
+    let primes: Vec<String> = five_digit_primes().map(|p| p.to_string()).collect();
+    write!(wordfile, "pub const PRIMES: &[&str] = &{:?};", primes).expect("write");
+    let digits: Vec<char> = ('0'..='9').collect();
+    write_frequencies(&mut wordfile, "PRIMES", &primes, &digits);
+}
+
+/// Count how often each symbol appears in a list, overall and in each position, so
+/// the counts for the built-in lists don't need making every time they're used.
+/// The counts are indexed by the symbols, which have to be sorted.
+fn write_frequencies(file: &mut File, name: &str, words: &[String], symbols: &[char]) {
+    let mut letters = vec![0; symbols.len()];
+    let mut positions = vec![vec![0; symbols.len()]; 5];
+    for word in words {
+        for (position, c) in word.chars().enumerate() {
+            let index = symbols.binary_search(&c).expect("a symbol of the alphabet");
+            letters[index] += 1;
+            positions[position][index] += 1;
+        }
+    }
+    write!(file, "pub const {}_LETTERS: &[i32] = &{:?};", name, letters).expect("write");
+    let positions: Vec<String> = positions.iter().map(|p| format!("&{:?}", p)).collect();
+    write!(
+        file,
+        "pub const {}_POSITIONS: [&[i32]; 5] = [{}];",
+        name,
+        positions.join(", ")
+    )
+    .expect("write");
+}
+
+/// Read a word list, failing the build on any entry that isn't five letters, since a
+/// bad line usually means the file was edited by hand or saved in the wrong format.
+/// The words are lowercased and deduplicated, but kept in order, since the answers
+/// are in the order the official game used them.
+fn read_list(path: &Path) -> Vec<String> {
+    println!("cargo:rerun-if-changed={}", path.display());
+    let file = File::open(path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+    let mut words = vec![];
+    let mut seen = HashSet::new();
+    for (line_no, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.unwrap_or_else(|e| panic!("{}:{}: {}", path.display(), line_no + 1, e));
+        let word = line.trim().to_lowercase();
+        if word.is_empty() {
+            continue;
+        }
+        if word.chars().count() != 5 || !word.chars().all(|c| c.is_ascii_lowercase()) {
+            panic!(
+                "{}:{}: \"{}\" isn't a five-letter word",
+                path.display(),
+                line_no + 1,
+                line
+            );
+        }
+        if seen.insert(word.clone()) {
+            words.push(word);
+        }
+    }
+    words
+}
+
+fn five_digit_primes() -> impl Iterator<Item = usize> {
+    const LIMIT: usize = 100_000;
+    let mut composite = vec![false; LIMIT];
+    for n in 2..LIMIT {
+        if !composite[n] {
+            (n * n..LIMIT).step_by(n).for_each(|m| composite[m] = true);
+        }
+    }
+    (10_000..LIMIT).filter(move |&n| !composite[n])
 }
@@ -0,0 +1,178 @@
+use crate::bench::SOLVED;
+use crate::dictionary::Dictionary;
+use crate::reverse::parse_row;
+use crate::MAX_GUESSES;
+use anyhow::{anyhow, Context, Result};
+use std::collections::BTreeMap;
+
+/// How many of the hardest puzzles the report lists
+const HARDEST_PUZZLES: usize = 5;
+
+/// One game from a batch, e.g. "alice: 1234 ⬛⬛🟨⬛⬛ 🟩🟩🟩🟩🟩"
+#[derive(Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// Who played, if the line says
+    pub player: Option<String>,
+    pub puzzle: u64,
+    /// `None` for a game that wasn't solved
+    pub guesses: Option<usize>,
+}
+
+impl Entry {
+    /// A game counts as one more guess than allowed if it wasn't solved
+    fn score(&self) -> usize {
+        self.guesses.unwrap_or(MAX_GUESSES + 1)
+    }
+}
+
+/// Read a game per line: optionally a player's name and a colon, then the puzzle
+/// number and the grid of colored squares, with or without spaces between rows.
+/// Blank lines are skipped.
+pub fn parse(text: &str) -> Result<Vec<Entry>> {
+    let mut entries = vec![];
+    for (line_no, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = parse_line(line).with_context(|| format!("line {}", line_no + 1))?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+fn parse_line(line: &str) -> Result<Entry> {
+    let (player, game) = match line.split_once(':') {
+        Some((player, game)) => (Some(player.trim().to_string()), game),
+        None => (None, line),
+    };
+    let game = game.trim_start();
+    let (puzzle, grid) = game.split_once(char::is_whitespace).unwrap_or((game, ""));
+    let puzzle = puzzle
+        .trim_start_matches('#')
+        .replace(',', "")
+        .parse()
+        .map_err(|_| anyhow!("\"{}\" isn't a puzzle number", puzzle))?;
+    // Some squares are followed by a selector asking for them to be drawn as emoji
+    let tiles: Vec<char> = grid
+        .chars()
+        .filter(|&c| !c.is_whitespace() && c != '\u{fe0f}')
+        .collect();
+    if tiles.is_empty() || !tiles.len().is_multiple_of(5) || tiles.len() > 5 * MAX_GUESSES {
+        return Err(anyhow!(
+            "The grid should have 5 squares in each of 1 to {} rows",
+            MAX_GUESSES
+        ));
+    }
+    let rows = tiles
+        .chunks(5)
+        .map(|row| parse_row(&row.iter().collect::<String>()))
+        .collect::<Result<Vec<u8>>>()?;
+    let guesses = (rows.last() == Some(&SOLVED)).then_some(rows.len());
+    Ok(Entry {
+        player,
+        puzzle,
+        guesses,
+    })
+}
+
+/// The average score of some games, counting failures as one more guess than allowed
+fn mean_score<'a>(entries: impl IntoIterator<Item = &'a Entry>) -> (usize, f64) {
+    let (count, total) = entries.into_iter().fold((0, 0), |(count, total), entry| {
+        (count + 1, total + entry.score())
+    });
+    (count, total as f64 / count.max(1) as f64)
+}
+
+/// Print the group's average, the puzzles it found hardest and how each player compares
+pub fn report(entries: &[Entry], dict: &Dictionary) {
+    let (games, mean) = mean_score(entries);
+    let solved = entries.iter().filter(|e| e.guesses.is_some()).count();
+    println!(
+        "{} games, {} solved, {:.2} guesses on average (counting a miss as {})",
+        games,
+        solved,
+        mean,
+        MAX_GUESSES + 1
+    );
+
+    let mut puzzles: BTreeMap<u64, Vec<&Entry>> = BTreeMap::new();
+    for entry in entries {
+        puzzles.entry(entry.puzzle).or_default().push(entry);
+    }
+    let mut hardest: Vec<(u64, usize, f64)> = puzzles
+        .iter()
+        .map(|(&puzzle, entries)| {
+            let (count, mean) = mean_score(entries.iter().copied());
+            (puzzle, count, mean)
+        })
+        .collect();
+    // The hardest first, and of those as hard as each other, the latest
+    hardest.sort_by(|a, b| b.2.total_cmp(&a.2).then(b.0.cmp(&a.0)));
+    println!("\nHardest puzzles:");
+    for (puzzle, count, mean) in hardest.iter().take(HARDEST_PUZZLES) {
+        println!(
+            "  #{} ({}): {:.2} guesses over {} games",
+            puzzle,
+            dict.daily_answer(*puzzle),
+            mean,
+            count
+        );
+    }
+
+    let mut players: BTreeMap<&str, Vec<&Entry>> = BTreeMap::new();
+    for entry in entries {
+        if let Some(player) = &entry.player {
+            players.entry(player).or_default().push(entry);
+        }
+    }
+    if players.is_empty() {
+        return;
+    }
+    let mut ranked: Vec<(&str, usize, usize, f64)> = players
+        .iter()
+        .map(|(&player, entries)| {
+            let (count, mean) = mean_score(entries.iter().copied());
+            let solved = entries.iter().filter(|e| e.guesses.is_some()).count();
+            (player, count, solved, mean)
+        })
+        .collect();
+    ranked.sort_by(|a, b| a.3.total_cmp(&b.3));
+    println!("\nPlayers, best first:");
+    for (player, count, solved, mean) in ranked {
+        println!(
+            "  {:<20} {:.2} guesses, solved {} of {}",
+            player, mean, solved, count
+        );
+    }
+}
+
+#[test]
+fn test_parse() {
+    let entries = parse(
+        "alice: 1,234 ⬛⬛🟨⬛⬛ 🟩⬛🟩🟨⬛ 🟩🟩🟩🟩🟩\n\n#1235 🟩🟩🟩🟩🟩\nbob: 1234 ⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛⬛\n",
+    )
+    .unwrap();
+    assert_eq!(
+        entries,
+        [
+            Entry {
+                player: Some("alice".to_string()),
+                puzzle: 1234,
+                guesses: Some(3)
+            },
+            Entry {
+                player: None,
+                puzzle: 1235,
+                guesses: Some(1)
+            },
+            Entry {
+                player: Some("bob".to_string()),
+                puzzle: 1234,
+                guesses: None
+            },
+        ]
+    );
+    assert_eq!(mean_score(&entries), (3, 11.0 / 3.0));
+    assert!(parse("alice: 1234 ⬛⬛🟨⬛").is_err());
+    assert!(parse("alice: today 🟩🟩🟩🟩🟩").is_err());
+}
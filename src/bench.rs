@@ -0,0 +1,450 @@
+use crate::cache;
+use crate::dictionary::Alphabet;
+use crate::strategy::{self, Frequencies, Strategy};
+use crate::{GuessResult, Tile, Word, MAX_GUESSES};
+use anyhow::Result;
+use indicatif::ProgressBar;
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+/// The pattern for a guess that matches the answer
+pub const SOLVED: u8 = 242;
+
+/// What cached feedback matrices are called
+const MATRIX_CACHE: &str = "matrix";
+
+/// What a strategy's mean number of guesses is cached as
+const MEAN_CACHE: &str = "mean";
+
+/// Stop autoplaying a game that has gone on this long, in case a strategy never finds the answer
+pub const GIVE_UP: usize = 20;
+
+/// Seeds the choice of sampled answers and the bootstrap, so every strategy is
+/// run on the same answers and the intervals don't change between runs
+const SAMPLE_SEED: u64 = 0x5a3e;
+
+/// How many times the games are resampled to find a confidence interval
+const BOOTSTRAP_RESAMPLES: usize = 1000;
+
+/// The feedback pattern for every pair of guess and answer, computed once and
+/// shared by everything that needs to evaluate guesses
+pub struct FeedbackMatrix {
+    /// The answers come first, so an answer's index is also its index as a guess
+    guesses: Vec<Word>,
+    answers: usize,
+    patterns: Vec<u8>,
+    /// How often each symbol appears in the answers, if it was given
+    frequencies: Option<Frequencies>,
+}
+
+impl FeedbackMatrix {
+    pub fn new<L: Tile>(
+        answers: &[Word],
+        extra: &[Word],
+        feedback: fn(Word, Word) -> GuessResult<L>,
+    ) -> Self {
+        let guesses: Vec<Word> = answers.iter().chain(extra.iter()).copied().collect();
+        let patterns = guesses
+            .iter()
+            .flat_map(|&guess| {
+                answers
+                    .iter()
+                    .map(move |&answer| feedback(guess, answer).pattern())
+            })
+            .collect();
+
+        FeedbackMatrix {
+            guesses,
+            answers: answers.len(),
+            patterns,
+            frequencies: None,
+        }
+    }
+
+    /// Like `new`, but reading the patterns from the cache if they've been worked out
+    /// before for the same words and variant, and caching them if not
+    pub fn cached<L: Tile>(
+        answers: &[Word],
+        extra: &[Word],
+        feedback: fn(Word, Word) -> GuessResult<L>,
+        variant: &str,
+    ) -> Self {
+        // The hash may change with the Rust version, which only means rebuilding the matrix
+        let mut hasher = DefaultHasher::new();
+        (variant, answers, extra).hash(&mut hasher);
+        let key = hasher.finish();
+
+        let len = (answers.len() + extra.len()) * answers.len();
+        if let Some(patterns) = cache::load(MATRIX_CACHE, key, len) {
+            return FeedbackMatrix {
+                guesses: answers.iter().chain(extra.iter()).copied().collect(),
+                answers: answers.len(),
+                patterns,
+                frequencies: None,
+            };
+        }
+        let matrix = FeedbackMatrix::new(answers, extra, feedback);
+        if let Err(e) = cache::save(MATRIX_CACHE, key, &matrix.patterns) {
+            eprintln!("Couldn't cache the feedback matrix: {:#}", e);
+        }
+        matrix
+    }
+
+    /// Give strategies the counts of the symbols in the answers, such as those made
+    /// when building for the built-in lists, so they don't count them every turn
+    pub fn with_frequencies(mut self, frequencies: Frequencies) -> Self {
+        self.frequencies = Some(frequencies);
+        self
+    }
+
+    pub fn guesses(&self) -> &[Word] {
+        &self.guesses
+    }
+
+    pub fn answers(&self) -> &[Word] {
+        &self.guesses[..self.answers]
+    }
+
+    pub fn guess_index(&self, word: Word) -> Option<usize> {
+        self.guesses.iter().position(|&g| g == word)
+    }
+
+    pub fn pattern(&self, guess: usize, answer: usize) -> u8 {
+        self.patterns[guess * self.answers + answer]
+    }
+
+    /// How often each symbol appears in the answers, only counting them if the
+    /// counts weren't given
+    pub fn answer_frequencies(&self, alphabet: &Alphabet) -> Cow<'_, Frequencies> {
+        match &self.frequencies {
+            Some(frequencies) => Cow::Borrowed(frequencies),
+            None => Cow::Owned(Frequencies::count(self.answers(), alphabet)),
+        }
+    }
+
+    /// How often each symbol appears in the candidates
+    pub fn frequencies(&self, alphabet: &Alphabet, candidates: &[usize]) -> Cow<'_, Frequencies> {
+        // Until a guess rules some out, every answer is a candidate
+        if candidates.len() == self.answers {
+            return self.answer_frequencies(alphabet);
+        }
+        let words: Vec<Word> = candidates.iter().map(|&c| self.guesses[c]).collect();
+        Cow::Owned(Frequencies::count(&words, alphabet))
+    }
+}
+
+/// The guesses a strategy made to find one answer
+pub struct Game {
+    pub answer: Word,
+    pub guesses: Vec<Word>,
+    /// How long choosing the guesses took. Later games are quicker, since they
+    /// reuse guesses already chosen by earlier ones.
+    pub elapsed: Duration,
+}
+
+impl Game {
+    pub fn solved(&self) -> bool {
+        self.guesses.len() <= MAX_GUESSES
+    }
+}
+
+/// The results of autoplaying every answer with one strategy
+pub struct Bench {
+    pub games: Vec<Game>,
+    pub elapsed: Duration,
+}
+
+impl Bench {
+    pub fn mean_guesses(&self) -> f64 {
+        self.games.iter().map(|g| g.guesses.len()).sum::<usize>() as f64 / self.games.len() as f64
+    }
+
+    pub fn failures(&self) -> usize {
+        self.games.iter().filter(|g| !g.solved()).count()
+    }
+
+    pub fn worst(&self) -> usize {
+        self.games
+            .iter()
+            .map(|g| g.guesses.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The games that used every guess or failed, from the most guesses
+    pub fn worst_cases(&self) -> Vec<&Game> {
+        let mut games: Vec<&Game> = self
+            .games
+            .iter()
+            .filter(|g| g.guesses.len() >= MAX_GUESSES)
+            .collect();
+        games.sort_by_key(|g| std::cmp::Reverse(g.guesses.len()));
+        games
+    }
+
+    /// The range the mean number of guesses over every answer is 95% likely to be
+    /// in, when only some of them were played, by bootstrapping the games
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let mut rng = StdRng::seed_from_u64(SAMPLE_SEED);
+        let n = self.games.len();
+        let mut means: Vec<f64> = (0..BOOTSTRAP_RESAMPLES)
+            .map(|_| {
+                let total: usize = (0..n)
+                    .map(|_| self.games[rng.gen_range(0..n)].guesses.len())
+                    .sum();
+                total as f64 / n as f64
+            })
+            .collect();
+        means.sort_by(f64::total_cmp);
+        let at =
+            |quantile: f64| means[(quantile * (BOOTSTRAP_RESAMPLES - 1) as f64).round() as usize];
+        (at(0.025), at(0.975))
+    }
+
+    /// How many games took each number of guesses, starting from one
+    pub fn distribution(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.worst()];
+        self.games
+            .iter()
+            .for_each(|g| counts[g.guesses.len() - 1] += 1);
+        counts
+    }
+}
+
+/// The strategy's mean number of guesses over every answer, if it's been cached for
+/// the same words and feedback
+pub fn cached_mean(matrix: &FeedbackMatrix, strategy: &dyn Strategy) -> Option<f64> {
+    let bytes = cache::load(MEAN_CACHE, mean_key(matrix, strategy), 8)?;
+    Some(f64::from_le_bytes(bytes.try_into().expect("8 bytes")))
+}
+
+pub fn cache_mean(matrix: &FeedbackMatrix, strategy: &dyn Strategy, mean: f64) -> Result<()> {
+    cache::save(MEAN_CACHE, mean_key(matrix, strategy), &mean.to_le_bytes())
+}
+
+fn mean_key(matrix: &FeedbackMatrix, strategy: &dyn Strategy) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (
+        strategy.name(),
+        &matrix.guesses,
+        matrix.answers,
+        &matrix.patterns,
+    )
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Autoplay every answer with the strategy, optionally always opening with the same
+/// guess, counting each game on the progress bar
+pub fn bench(
+    matrix: &FeedbackMatrix,
+    alphabet: &Alphabet,
+    strategy: &dyn Strategy,
+    first_guess: Option<Word>,
+    progress: &ProgressBar,
+) -> Bench {
+    let answers: Vec<usize> = (0..matrix.answers().len()).collect();
+    bench_answers(matrix, alphabet, strategy, first_guess, &answers, progress)
+}
+
+/// Up to `count` of the answers' indices picked at random, the same ones each time
+pub fn sample_answers(matrix: &FeedbackMatrix, count: usize) -> Vec<usize> {
+    let mut answers: Vec<usize> = (0..matrix.answers().len()).collect();
+    answers.shuffle(&mut StdRng::seed_from_u64(SAMPLE_SEED));
+    answers.truncate(count);
+    answers.sort_unstable();
+    answers
+}
+
+/// Autoplay some of the answers, given by their indices, like [bench]. Every answer
+/// is still a candidate while playing.
+pub fn bench_answers(
+    matrix: &FeedbackMatrix,
+    alphabet: &Alphabet,
+    strategy: &dyn Strategy,
+    first_guess: Option<Word>,
+    answers: &[usize],
+    progress: &ProgressBar,
+) -> Bench {
+    let start = Instant::now();
+    let mut memo = opening_memo(matrix, first_guess);
+    let games = answers
+        .iter()
+        .map(|&answer| {
+            let start = Instant::now();
+            let guesses = autoplay(matrix, alphabet, strategy, answer, &mut memo)
+                .into_iter()
+                .map(|guess| matrix.guesses()[guess])
+                .collect();
+            progress.inc(1);
+            Game {
+                answer: matrix.answers()[answer],
+                guesses,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect();
+
+    Bench {
+        games,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Autoplay every answer with every pairing of strategy and first guess, benchmarking
+/// the pairings in parallel. They all count their games on the same progress bar.
+pub fn sweep<'a>(
+    matrix: &FeedbackMatrix,
+    alphabet: &Alphabet,
+    strategies: &[&'a dyn Strategy],
+    first_guesses: &[Option<Word>],
+    progress: &ProgressBar,
+) -> Vec<(&'a dyn Strategy, Option<Word>, Bench)> {
+    let pairings: Vec<(&dyn Strategy, Option<Word>)> = strategies
+        .iter()
+        .flat_map(|&strategy| first_guesses.iter().map(move |&guess| (strategy, guess)))
+        .collect();
+    pairings
+        .into_par_iter()
+        .map(|(strategy, first_guess)| {
+            let result = bench(matrix, alphabet, strategy, first_guess, progress);
+            (strategy, first_guess, result)
+        })
+        .collect()
+}
+
+/// Write one row per game of each strategy's results
+pub fn write_csv(mut out: impl Write, results: &[(&str, &Bench)]) -> io::Result<()> {
+    writeln!(out, "strategy,answer,guesses,sequence,time_ms")?;
+    for (strategy, bench) in results {
+        for game in &bench.games {
+            writeln!(
+                out,
+                "{},{},{},{},{:.3}",
+                csv_field(strategy),
+                csv_field(&game.answer.to_string()),
+                game.guesses.len(),
+                csv_field(&game.guesses.iter().join(" ")),
+                game.elapsed.as_secs_f64() * 1000.0
+            )?;
+        }
+    }
+    out.flush()
+}
+
+/// Quote a field if it contains anything that would confuse a CSV reader
+pub fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Autoplay a single answer with the strategy, returning the guesses made
+pub fn autoplay_answer(
+    matrix: &FeedbackMatrix,
+    alphabet: &Alphabet,
+    strategy: &dyn Strategy,
+    answer: Word,
+    first_guess: Option<Word>,
+) -> Vec<Word> {
+    let answer = matrix
+        .answers()
+        .iter()
+        .position(|&a| a == answer)
+        .expect("answer isn't in the matrix");
+    autoplay(
+        matrix,
+        alphabet,
+        strategy,
+        answer,
+        &mut opening_memo(matrix, first_guess),
+    )
+    .into_iter()
+    .map(|guess| matrix.guesses()[guess])
+    .collect()
+}
+
+/// Strategies are deterministic, so the guess only depends on the feedback so far.
+/// Remembering it means each position in the game tree is only evaluated once.
+/// Pinning the opening guess is just a matter of remembering it up front.
+fn opening_memo(matrix: &FeedbackMatrix, first_guess: Option<Word>) -> HashMap<Vec<u8>, usize> {
+    let mut memo = HashMap::new();
+    if let Some(word) = first_guess {
+        let guess = matrix
+            .guess_index(word)
+            .expect("first guess isn't in the matrix");
+        memo.insert(vec![], guess);
+    }
+    memo
+}
+
+/// Play one answer, returning the indices of the guesses made
+fn autoplay(
+    matrix: &FeedbackMatrix,
+    alphabet: &Alphabet,
+    strategy: &dyn Strategy,
+    answer: usize,
+    memo: &mut HashMap<Vec<u8>, usize>,
+) -> Vec<usize> {
+    let mut candidates: Vec<usize> = (0..matrix.answers().len()).collect();
+    let mut history = vec![];
+    let mut guesses = vec![];
+    loop {
+        let guess = *memo
+            .entry(history.clone())
+            .or_insert_with(|| strategy::choose(strategy, matrix, alphabet, &candidates));
+        guesses.push(guess);
+
+        let pattern = matrix.pattern(guess, answer);
+        if pattern == SOLVED || guesses.len() >= GIVE_UP {
+            return guesses;
+        }
+        history.push(pattern);
+        candidates.retain(|&c| matrix.pattern(guess, c) == pattern);
+    }
+}
+
+/// The first few answers of the official game, which give the matrix for tests
+/// enough different feedback to tell them apart
+#[cfg(test)]
+pub fn test_matrix() -> FeedbackMatrix {
+    let words: Vec<Word> = ["cigar", "rebut", "sissy", "humph", "awake", "blush"]
+        .iter()
+        .map(|&w| w.try_into().unwrap())
+        .collect();
+    FeedbackMatrix::new(&words, &[], crate::guess_word)
+}
+
+#[test]
+fn test_confidence_interval() {
+    let matrix = test_matrix();
+    let words = matrix.answers();
+    let game = |guesses: usize| Game {
+        answer: words[0],
+        guesses: words.iter().copied().cycle().take(guesses).collect(),
+        elapsed: Duration::ZERO,
+    };
+    let bench = Bench {
+        games: (0..40).map(|i| game(3 + i % 2)).collect(),
+        elapsed: Duration::ZERO,
+    };
+    let (low, high) = bench.confidence_interval();
+    assert!(low < 3.5 && 3.5 < high);
+    assert!(low > 3.2 && high < 3.8);
+
+    let sample = sample_answers(&matrix, 4);
+    assert_eq!(sample.len(), 4);
+    assert_eq!(sample, sample_answers(&matrix, 4));
+    assert_eq!(sample_answers(&matrix, 10).len(), words.len());
+}
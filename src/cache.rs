@@ -0,0 +1,45 @@
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+/// Keep computed data that's slow to build, such as feedback matrices, in the
+/// platform's cache directory, named by a hash of everything it was built from.
+/// Anything in the cache can be deleted at any time, and is rebuilt when needed.
+fn cache_dir() -> Result<PathBuf> {
+    let dir = dirs::cache_dir().ok_or_else(|| anyhow!("Can't find a directory for the cache"))?;
+    Ok(dir.join("wordle_solve"))
+}
+
+fn path(kind: &str, key: u64) -> Result<PathBuf> {
+    Ok(cache_dir()?.join(format!("{}-{:016x}.bin", kind, key)))
+}
+
+/// The cached bytes, if there are any of the expected length. Anything else is a
+/// stale or damaged entry, and is treated as missing.
+pub fn load(kind: &str, key: u64, len: usize) -> Option<Vec<u8>> {
+    let bytes = fs::read(path(kind, key).ok()?).ok()?;
+    (bytes.len() == len).then_some(bytes)
+}
+
+pub fn save(kind: &str, key: u64, bytes: &[u8]) -> Result<()> {
+    let path = path(kind, key)?;
+    let dir = cache_dir()?;
+    fs::create_dir_all(&dir).with_context(|| format!("creating {}", dir.display()))?;
+    // Write to the side and rename, so a partly written file is never read
+    let partial = path.with_extension("partial");
+    fs::write(&partial, bytes).with_context(|| format!("writing {}", partial.display()))?;
+    fs::rename(&partial, &path).with_context(|| format!("writing {}", path.display()))
+}
+
+/// Delete everything in the cache, returning how many files there were
+pub fn clear() -> Result<usize> {
+    let dir = cache_dir()?;
+    if !dir.exists() {
+        return Ok(0);
+    }
+    let count = fs::read_dir(&dir)
+        .with_context(|| format!("reading {}", dir.display()))?
+        .count();
+    fs::remove_dir_all(&dir).with_context(|| format!("deleting {}", dir.display()))?;
+    Ok(count)
+}
@@ -0,0 +1,70 @@
+use crate::Word;
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use std::fmt::Write;
+
+/// Seeds the bytes mixed into a code. Changing it invalidates every code already shared.
+const KEY: u32 = 0x5eed_1e77;
+
+/// Turn a word into a code that can be shared without giving the word away. This only
+/// hides the word from a glance, since anyone with this program can decode it.
+pub fn encode(word: Word) -> String {
+    let mut bytes: Vec<u8> = word.to_string().into_bytes();
+    bytes.push(checksum(&bytes));
+    scramble(&mut bytes);
+    bytes.iter().fold(String::new(), |mut code, b| {
+        let _ = write!(code, "{:02x}", b);
+        code
+    })
+}
+
+/// Recover the word from a code made by `encode`
+pub fn decode(code: &str) -> Result<Word> {
+    let invalid = || anyhow!("\"{}\" isn't a valid challenge code", code);
+    let mut bytes = code
+        .trim()
+        .chars()
+        .chunks(2)
+        .into_iter()
+        .map(|pair| u8::from_str_radix(&pair.collect::<String>(), 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|_| invalid())?;
+    scramble(&mut bytes);
+
+    let check = bytes.pop().ok_or_else(invalid)?;
+    if check != checksum(&bytes) {
+        return Err(invalid());
+    }
+    let word = String::from_utf8(bytes).map_err(|_| invalid())?;
+    Word::try_from(word.as_str()).map_err(|_| invalid())
+}
+
+/// Catches codes that were mistyped or cut short
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes
+        .iter()
+        .fold(0u8, |sum, &b| sum.rotate_left(3).wrapping_add(b))
+}
+
+/// XOR the bytes with a pseudo-random stream, which undoes itself when repeated
+fn scramble(bytes: &mut [u8]) {
+    let mut state = KEY;
+    for b in bytes {
+        state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        *b ^= (state >> 16) as u8;
+    }
+}
+
+#[test]
+fn test_challenge_code() {
+    let word: Word = "crane".try_into().unwrap();
+    let code = encode(word);
+    assert!(!code.contains("crane"));
+    assert_eq!(decode(&code).unwrap(), word);
+
+    let mut typo = code.clone();
+    typo.replace_range(0..1, if code.starts_with('0') { "1" } else { "0" });
+    assert!(decode(&typo).is_err());
+    assert!(decode(&code[..code.len() - 2]).is_err());
+    assert!(decode("not a code").is_err());
+}
@@ -0,0 +1,135 @@
+use crate::bench::FeedbackMatrix;
+use crate::dictionary::Alphabet;
+use crate::i18n::Text;
+use crate::strategy::{self, partition_sizes, Strategy};
+use crate::Word;
+
+/// Reviews each guess in Play against what the solver would have done
+pub struct Coach<'a> {
+    matrix: FeedbackMatrix,
+    alphabet: &'a Alphabet,
+    strategy: &'a dyn Strategy,
+    /// The answers still possible given the guesses so far
+    candidates: Vec<usize>,
+    /// The grade of each guess reviewed
+    grades: Vec<char>,
+}
+
+impl<'a> Coach<'a> {
+    /// The matrix needs to include every word the player is allowed to guess
+    pub fn new(matrix: FeedbackMatrix, alphabet: &'a Alphabet, strategy: &'a dyn Strategy) -> Self {
+        let candidates = (0..matrix.answers().len()).collect();
+        Coach {
+            matrix,
+            alphabet,
+            strategy,
+            candidates,
+            grades: vec![],
+        }
+    }
+
+    /// Show the grades of the guesses reviewed, at the end of the game
+    pub fn summary(&self) {
+        if !self.grades.is_empty() {
+            println!("{}", Text::CoachSummary(&self.grades));
+        }
+    }
+
+    /// Show how much the guess narrowed down the answer, compared to the solver's
+    /// choice, returning the guess's grade
+    pub fn review(&mut self, guess: Word, answer: Word) -> char {
+        let guess = self
+            .matrix
+            .guess_index(guess)
+            .expect("guess isn't in the matrix");
+        let answer = self
+            .matrix
+            .guess_index(answer)
+            .expect("answer isn't in the matrix");
+        let pick = strategy::choose(self.strategy, &self.matrix, self.alphabet, &self.candidates);
+
+        // Judge by the average number of answers left, so luck doesn't count
+        let yours = self.expected_remaining(guess);
+        let solvers = self.expected_remaining(pick);
+        let (grade, information, best) = self.grade(guess);
+        self.grades.push(grade);
+
+        let before = self.candidates.len();
+        let pattern = self.matrix.pattern(guess, answer);
+        self.candidates
+            .retain(|&c| self.matrix.pattern(guess, c) == pattern);
+        println!("{}", Text::CoachNarrowed(before, self.candidates.len()));
+        println!("{}", Text::CoachGrade(grade, information, best));
+
+        if pick == guess {
+            println!("{}", Text::CoachSameGuess);
+            return grade;
+        }
+        println!(
+            "{}",
+            Text::CoachCompare(yours, self.matrix.guesses()[pick], solvers)
+        );
+        grade
+    }
+
+    /// Grade a guess from A to F by how much it's expected to find out about the
+    /// answer, compared to the best guess there was. Also returns how much each
+    /// finds out, in bits.
+    fn grade(&self, guess: usize) -> (char, f64, f64) {
+        let information = self.information(guess);
+        let best = (0..self.matrix.guesses().len())
+            .map(|g| self.information(g))
+            .fold(information, f64::max);
+        // With one candidate left there's nothing to find out, only the answer to guess
+        let ratio = if best > 0.0 {
+            information / best
+        } else if self.candidates == [guess] {
+            1.0
+        } else {
+            0.0
+        };
+        (grade_for(ratio), information, best)
+    }
+
+    /// The expected information from the guess's feedback, in bits
+    fn information(&self, guess: usize) -> f64 {
+        let n = self.candidates.len() as f64;
+        let sizes = partition_sizes(&self.matrix, guess, &self.candidates);
+        let sum: f64 = sizes
+            .iter()
+            .filter(|&&s| s > 1)
+            .map(|&s| s as f64 * (s as f64).log2())
+            .sum();
+        n.log2() - sum / n
+    }
+
+    /// The number of candidates the guess would leave, averaged over the candidates
+    fn expected_remaining(&self, guess: usize) -> f64 {
+        let sizes = partition_sizes(&self.matrix, guess, &self.candidates);
+        sizes.iter().map(|&s| (s * s) as f64).sum::<f64>() / self.candidates.len() as f64
+    }
+}
+
+/// The grade for finding this fraction of what the best guess would
+fn grade_for(ratio: f64) -> char {
+    match ratio {
+        r if r >= 0.95 => 'A',
+        r if r >= 0.85 => 'B',
+        r if r >= 0.7 => 'C',
+        r if r >= 0.5 => 'D',
+        _ => 'F',
+    }
+}
+
+#[test]
+fn test_grade() {
+    use crate::bench::test_matrix;
+    let alphabet = Alphabet::letters();
+    let coach = Coach::new(test_matrix(), &alphabet, &strategy::Frequency);
+    // "rebut" tells every other answer apart
+    let (grade, information, best) = coach.grade(1);
+    assert_eq!(grade, 'A');
+    assert!((information - best).abs() < 1e-9);
+    assert_eq!(grade_for(0.6), 'D');
+    assert_eq!(grade_for(0.1), 'F');
+}
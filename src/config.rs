@@ -0,0 +1,192 @@
+use anyhow::{anyhow, Context, Result};
+use owo_colors::AnsiColors;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// The settings file, a JSON object, in the `wordle_solve` config directory
+const CONFIG_FILENAME: &str = "config.json";
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// The colors of a tile's letter and of the tile itself
+#[derive(Debug, Clone, Copy)]
+pub struct TileColors {
+    pub fg: AnsiColors,
+    pub bg: AnsiColors,
+}
+
+/// The colors of each kind of tile. Peaks' "earlier" tiles use the present colors.
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub correct: TileColors,
+    pub present: TileColors,
+    pub absent: TileColors,
+    pub empty: TileColors,
+    /// Peaks' tiles for when the answer's letter comes later in the alphabet
+    pub later: TileColors,
+}
+
+const fn colors(fg: AnsiColors, bg: AnsiColors) -> TileColors {
+    TileColors { fg, bg }
+}
+
+impl Theme {
+    pub const DEFAULT: Theme = Theme {
+        correct: colors(AnsiColors::Black, AnsiColors::Green),
+        present: colors(AnsiColors::Black, AnsiColors::Yellow),
+        absent: colors(AnsiColors::BrightWhite, AnsiColors::BrightBlack),
+        empty: colors(AnsiColors::White, AnsiColors::Black),
+        later: colors(AnsiColors::BrightWhite, AnsiColors::Blue),
+    };
+
+    /// Orange and blue, like the real game's high contrast mode
+    pub const HIGH_CONTRAST: Theme = Theme {
+        correct: colors(AnsiColors::Black, AnsiColors::Red),
+        present: colors(AnsiColors::Black, AnsiColors::Cyan),
+        ..Theme::DEFAULT
+    };
+
+    /// The theme chosen in the config file, or the default one if there isn't one
+    pub fn current() -> &'static Theme {
+        THEME.get_or_init(|| Theme::DEFAULT)
+    }
+
+    /// Read the config file, if there is one, and use the theme it chooses from then on
+    pub fn load() -> Result<()> {
+        let theme = match read_config()? {
+            Some(config) => Theme::from_config(&config).context("in the config file")?,
+            None => Theme::DEFAULT,
+        };
+        THEME
+            .set(theme)
+            .map_err(|_| anyhow!("The theme has already been chosen"))
+    }
+
+    /// A config looks like `{"theme": "mine", "themes": {"mine": {"correct": {"fg":
+    /// "black", "bg": "magenta"}}}}`. Tiles a theme leaves out keep the default colors,
+    /// and tiles given at the top level change whichever theme is chosen.
+    fn from_config(config: &Map<String, Value>) -> Result<Self> {
+        let name = match config.get("theme") {
+            None => "default",
+            Some(Value::String(name)) => name,
+            Some(_) => return Err(anyhow!("\"theme\" should be the name of a theme")),
+        };
+        let themes = match config.get("themes") {
+            None => None,
+            Some(Value::Object(themes)) => Some(themes),
+            Some(_) => return Err(anyhow!("\"themes\" should be an object")),
+        };
+        let mut theme = match themes.and_then(|themes| themes.get(name)) {
+            Some(Value::Object(custom)) => {
+                let mut theme = Theme::DEFAULT;
+                theme
+                    .set_tiles(custom)
+                    .with_context(|| format!("in theme \"{}\"", name))?;
+                theme
+            }
+            Some(_) => return Err(anyhow!("Theme \"{}\" should be an object", name)),
+            None => match name {
+                "default" => Theme::DEFAULT,
+                "high-contrast" => Theme::HIGH_CONTRAST,
+                _ => return Err(anyhow!("There's no theme called \"{}\"", name)),
+            },
+        };
+        theme.set_tiles(config)?;
+        Ok(theme)
+    }
+
+    /// Change the colors of the tiles the object names, ignoring any other keys
+    fn set_tiles(&mut self, object: &Map<String, Value>) -> Result<()> {
+        for (name, tile) in [
+            ("correct", &mut self.correct),
+            ("present", &mut self.present),
+            ("absent", &mut self.absent),
+            ("empty", &mut self.empty),
+            ("later", &mut self.later),
+        ] {
+            let colors = match object.get(name) {
+                None => continue,
+                Some(Value::Object(colors)) => colors,
+                Some(_) => return Err(anyhow!("\"{}\" should be an object", name)),
+            };
+            for (key, color) in [("fg", &mut tile.fg), ("bg", &mut tile.bg)] {
+                if let Some(value) = colors.get(key) {
+                    *color = value.as_str().and_then(parse_color).ok_or_else(|| {
+                        anyhow!("Invalid color for \"{}\" {}: {}", name, key, value)
+                    })?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One of the terminal's 16 colors, such as "green" or "bright-black"
+fn parse_color(name: &str) -> Option<AnsiColors> {
+    let (bright, base) = match name.strip_prefix("bright-") {
+        Some(base) => (true, base),
+        None => (false, name),
+    };
+    let color = match (bright, base) {
+        (false, "black") => AnsiColors::Black,
+        (false, "red") => AnsiColors::Red,
+        (false, "green") => AnsiColors::Green,
+        (false, "yellow") => AnsiColors::Yellow,
+        (false, "blue") => AnsiColors::Blue,
+        (false, "magenta") => AnsiColors::Magenta,
+        (false, "cyan") => AnsiColors::Cyan,
+        (false, "white") => AnsiColors::White,
+        (true, "black") => AnsiColors::BrightBlack,
+        (true, "red") => AnsiColors::BrightRed,
+        (true, "green") => AnsiColors::BrightGreen,
+        (true, "yellow") => AnsiColors::BrightYellow,
+        (true, "blue") => AnsiColors::BrightBlue,
+        (true, "magenta") => AnsiColors::BrightMagenta,
+        (true, "cyan") => AnsiColors::BrightCyan,
+        (true, "white") => AnsiColors::BrightWhite,
+        _ => return None,
+    };
+    Some(color)
+}
+
+fn config_path() -> Option<PathBuf> {
+    Some(
+        dirs::config_dir()?
+            .join("wordle_solve")
+            .join(CONFIG_FILENAME),
+    )
+}
+
+fn read_config() -> Result<Option<Map<String, Value>>> {
+    let path = match config_path() {
+        Some(path) if path.exists() => path,
+        _ => return Ok(None),
+    };
+    let text = fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+    match serde_json::from_str(&text).with_context(|| format!("reading {}", path.display()))? {
+        Value::Object(config) => Ok(Some(config)),
+        _ => Err(anyhow!("{} should contain a JSON object", path.display())),
+    }
+}
+
+#[test]
+fn test_themes() {
+    let config = |text| match serde_json::from_str(text).unwrap() {
+        Value::Object(config) => Theme::from_config(&config),
+        _ => unreachable!(),
+    };
+    let theme = config(r#"{"theme": "high-contrast", "absent": {"bg": "bright-red"}}"#).unwrap();
+    assert_eq!(theme.correct.bg, AnsiColors::Red);
+    assert_eq!(theme.absent.bg, AnsiColors::BrightRed);
+    assert_eq!(theme.absent.fg, AnsiColors::BrightWhite);
+
+    let theme =
+        config(r#"{"theme": "mine", "themes": {"mine": {"present": {"fg": "blue"}}}}"#).unwrap();
+    assert_eq!(theme.present.fg, AnsiColors::Blue);
+    assert_eq!(theme.present.bg, AnsiColors::Yellow);
+
+    assert!(config(r#"{"theme": "missing"}"#).is_err());
+    assert!(config(r#"{"correct": {"fg": "mauve"}}"#).is_err());
+}
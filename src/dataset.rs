@@ -0,0 +1,116 @@
+use crate::bench::{FeedbackMatrix, GIVE_UP, SOLVED};
+use crate::dictionary::Alphabet;
+use crate::strategy::{self, Strategy};
+use anyhow::{Context, Result};
+use indicatif::ProgressBar;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::Write;
+
+/// Seeds the choice of answers and the noise, so the same options always give
+/// the same dataset
+const SEED: u64 = 0xda7a;
+
+/// Autoplay `games` answers picked at random, writing a JSON object per turn with
+/// the candidates left before it, the guess made and the feedback it got. With
+/// `noise`, that fraction of guesses are a random candidate instead of the
+/// strategy's choice, to play more like a person does.
+pub fn generate(
+    matrix: &FeedbackMatrix,
+    alphabet: &Alphabet,
+    strategy: &dyn Strategy,
+    games: usize,
+    noise: f64,
+    mut out: impl Write,
+    progress: &ProgressBar,
+) -> Result<()> {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    // The strategy's choice only depends on the guesses and feedback so far
+    let mut memo: HashMap<Vec<(usize, u8)>, usize> = HashMap::new();
+    for game in 0..games {
+        let answer = rng.gen_range(0..matrix.answers().len());
+        let mut candidates: Vec<usize> = (0..matrix.answers().len()).collect();
+        let mut history = vec![];
+        loop {
+            let guess = if rng.gen_bool(noise) {
+                candidates[rng.gen_range(0..candidates.len())]
+            } else {
+                *memo
+                    .entry(history.clone())
+                    .or_insert_with(|| strategy::choose(strategy, matrix, alphabet, &candidates))
+            };
+            let pattern = matrix.pattern(guess, answer);
+            let words: Vec<String> = candidates
+                .iter()
+                .map(|&c| matrix.answers()[c].to_string())
+                .collect();
+            let record = json!({
+                "game": game,
+                "turn": history.len() + 1,
+                "answer": matrix.answers()[answer].to_string(),
+                "candidates": words,
+                "guess": matrix.guesses()[guess].to_string(),
+                "feedback": feedback_codes(pattern),
+                "pattern": pattern,
+            });
+            writeln!(out, "{}", record).context("writing the dataset")?;
+            history.push((guess, pattern));
+            if pattern == SOLVED || history.len() >= GIVE_UP {
+                break;
+            }
+            candidates.retain(|&c| matrix.pattern(guess, c) == pattern);
+        }
+        progress.inc(1);
+    }
+    out.flush().context("writing the dataset")
+}
+
+/// Each tile's code from first to last, e.g. "20100", where 2 is a correct letter
+fn feedback_codes(mut pattern: u8) -> String {
+    (0..5)
+        .map(|_| {
+            let code = pattern % 3;
+            pattern /= 3;
+            char::from(b'0' + code)
+        })
+        .collect()
+}
+
+#[test]
+fn test_generate() {
+    use crate::bench::test_matrix;
+    use crate::strategy::Frequency;
+    let matrix = test_matrix();
+    let words = matrix.answers();
+    let alphabet = Alphabet::letters();
+    let mut out = vec![];
+    generate(
+        &matrix,
+        &alphabet,
+        &Frequency,
+        4,
+        0.5,
+        &mut out,
+        &ProgressBar::hidden(),
+    )
+    .unwrap();
+    let records: Vec<serde_json::Value> = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    // Each game ends when it's solved
+    let ends: Vec<_> = records
+        .iter()
+        .filter(|r| r["feedback"] == "22222")
+        .collect();
+    assert_eq!(ends.len(), 4);
+    assert!(ends.iter().all(|r| r["guess"] == r["answer"]));
+    assert_eq!(
+        records[0]["candidates"].as_array().unwrap().len(),
+        words.len()
+    );
+    assert_eq!(feedback_codes(1 + 2 * 9), "10200");
+}
@@ -0,0 +1,276 @@
+use crate::schedule::Schedule;
+use crate::source::WordSource;
+use crate::strategy::Frequencies;
+use crate::{Word, WordError, WordleError};
+use anyhow::{anyhow, Result};
+use std::cmp::Reverse;
+use std::str::FromStr;
+
+/// The set of symbols words are spelled with, kept in sorted order
+#[derive(Debug, Clone)]
+pub struct Alphabet(Vec<char>);
+
+impl Alphabet {
+    pub fn letters() -> Self {
+        Alphabet(('a'..='z').collect())
+    }
+
+    pub fn digits() -> Self {
+        Alphabet(('0'..='9').collect())
+    }
+
+    pub fn symbols(&self) -> &[char] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        self.index(c).is_some()
+    }
+
+    pub fn index(&self, c: char) -> Option<usize> {
+        self.0.binary_search(&c).ok()
+    }
+
+    /// Parse a word, rejecting any symbols outside the alphabet
+    pub fn parse_word(&self, s: &str) -> Result<Word, WordleError> {
+        self.check_word(s)
+            .map_err(|reason| WordleError::InvalidWord {
+                word: s.trim().to_string(),
+                reason,
+            })
+    }
+
+    /// Like `parse_word`, but leaves describing the word to the caller
+    pub fn check_word(&self, s: &str) -> Result<Word, WordError> {
+        let word = Word::try_from(s)?;
+        match word.0.iter().find(|&&c| !self.contains(c)) {
+            Some(&c) if c.is_numeric() => Err(WordError::Digit(c)),
+            Some(&c) => Err(WordError::Symbol(c)),
+            None => Ok(word),
+        }
+    }
+}
+
+impl FromStr for Alphabet {
+    type Err = anyhow::Error;
+
+    /// Parses a list of symbols and inclusive ranges, such as `a-z'`
+    fn from_str(spec: &str) -> Result<Self> {
+        let chars: Vec<char> = spec.chars().collect();
+        let mut symbols = vec![];
+        let mut i = 0;
+        while i < chars.len() {
+            if i + 2 < chars.len() && chars[i + 1] == '-' {
+                let (lo, hi) = (chars[i], chars[i + 2]);
+                if lo > hi {
+                    return Err(anyhow!("Invalid range \"{}-{}\"", lo, hi));
+                }
+                symbols.extend(lo..=hi);
+                i += 3;
+            } else {
+                symbols.push(chars[i]);
+                i += 1;
+            }
+        }
+        symbols.sort_unstable();
+        symbols.dedup();
+
+        if symbols.is_empty() {
+            return Err(anyhow!("The alphabet is empty"));
+        }
+        // Uppercase is how hints mark a letter in the correct location
+        if let Some(c) = symbols.iter().find(|c| c.is_uppercase()) {
+            return Err(anyhow!("The alphabet can't contain uppercase '{}'", c));
+        }
+        Ok(Alphabet(symbols))
+    }
+}
+
+/// The word lists a game draws its answers and guesses from
+pub struct Dictionary {
+    pub alphabet: Alphabet,
+    /// Words that can be the answer
+    pub answers: Vec<Word>,
+    /// Words that are accepted as guesses, but can't be the answer
+    pub extra: Vec<Word>,
+    /// Both lists together, sorted so checking guesses doesn't scan them
+    accepted: Vec<Word>,
+    /// The official daily answers, if they've been supplied
+    pub schedule: Option<Schedule>,
+    /// How often each symbol appears in the answers and extra words, when the
+    /// source knew without counting. Forgotten once the lists change.
+    frequencies: Option<(Frequencies, Frequencies)>,
+}
+
+impl Dictionary {
+    /// Load the word lists from wherever they're kept
+    pub fn load(source: &dyn WordSource) -> Result<Self> {
+        let answers = source.answers()?;
+        let extra = source.extra()?;
+        let accepted = source.accepted().unwrap_or_else(|| {
+            let mut accepted: Vec<Word> = answers.iter().chain(extra.iter()).copied().collect();
+            accepted.sort();
+            accepted.dedup();
+            accepted
+        });
+        Ok(Dictionary {
+            alphabet: source.alphabet().clone(),
+            answers,
+            extra,
+            accepted,
+            schedule: None,
+            frequencies: source.frequencies(),
+        })
+    }
+
+    /// The words to consider, optionally including those that can't be the answer
+    pub fn words(&self, more_words: bool) -> Vec<Word> {
+        if more_words {
+            self.answers
+                .iter()
+                .chain(self.extra.iter())
+                .copied()
+                .collect()
+        } else {
+            self.answers.clone()
+        }
+    }
+
+    /// How often each symbol appears in the words to consider, without counting them
+    /// again for the built-in lists
+    pub fn frequencies(&self, more_words: bool) -> Frequencies {
+        match &self.frequencies {
+            Some((answers, _)) if !more_words => answers.clone(),
+            Some((answers, extra)) => answers.clone().add(extra),
+            None => Frequencies::count(&self.words(more_words), &self.alphabet),
+        }
+    }
+
+    pub fn is_accepted(&self, word: &Word) -> bool {
+        self.accepted.binary_search(word).is_ok()
+    }
+
+    /// Make a word a possible answer, for games played with a word of someone's choosing
+    pub fn add_answer(&mut self, word: Word) {
+        if self.answers.contains(&word) {
+            return;
+        }
+        self.answers.push(word);
+        self.extra.retain(|&w| w != word);
+        self.frequencies = None;
+        if let Err(index) = self.accepted.binary_search(&word) {
+            self.accepted.insert(index, word);
+        }
+    }
+
+    pub fn random_answer(&self) -> Word {
+        self.answers[rand::random::<usize>() % self.answers.len()]
+    }
+
+    /// A random answer of those that pass the test, if any do
+    pub fn random_answer_where(&self, test: impl Fn(&Word) -> bool) -> Option<Word> {
+        let answers: Vec<Word> = self.answers.iter().filter(|w| test(w)).copied().collect();
+        if answers.is_empty() {
+            return None;
+        }
+        Some(answers[rand::random::<usize>() % answers.len()])
+    }
+
+    /// The accepted words closest to what was typed, for suggesting what a guess
+    /// that isn't in the list was meant to be. Of words the same distance away, those
+    /// sharing more letters with it come first, so swapped letters beat wrong ones,
+    /// and then answers.
+    pub fn nearest(&self, input: &str, limit: usize) -> Vec<Word> {
+        const MAX_DISTANCE: usize = 2;
+        let typed: Vec<char> = input.trim().chars().flat_map(char::to_lowercase).collect();
+        let shared = |word: &Word| {
+            let mut letters = word.0.to_vec();
+            typed
+                .iter()
+                .filter(|c| match letters.iter().position(|l| l == *c) {
+                    Some(i) => {
+                        letters.swap_remove(i);
+                        true
+                    }
+                    None => false,
+                })
+                .count()
+        };
+        let mut near: Vec<(usize, bool, Word)> = self
+            .answers
+            .iter()
+            .map(|w| (w, false))
+            .chain(self.extra.iter().map(|w| (w, true)))
+            .map(|(&word, extra)| (edit_distance(&typed, &word.0), extra, word))
+            .filter(|&(distance, ..)| distance <= MAX_DISTANCE)
+            .collect();
+        near.sort_by_key(|&(distance, extra, word)| (distance, Reverse(shared(&word)), extra));
+        near.into_iter()
+            .take(limit)
+            .map(|(.., word)| word)
+            .collect()
+    }
+
+    /// The answer to a daily puzzle, from the schedule if it has one. Otherwise, the
+    /// built-in answers are in the order the official game used them, so a puzzle's
+    /// number is its answer's position, starting over once they've all been used.
+    /// A scheduled answer might not be in the list, so add it before playing.
+    pub fn daily_answer(&self, puzzle: u64) -> Word {
+        if let Some(word) = self.schedule.as_ref().and_then(|s| s.get(puzzle)) {
+            return word;
+        }
+        self.answers[(puzzle % self.answers.len() as u64) as usize]
+    }
+}
+
+/// How many symbols have to be added, removed, changed or swapped with the next
+/// one to turn one spelling into the other
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+#[test]
+fn test_nearest() {
+    let chars = |s: &str| s.chars().collect::<Vec<_>>();
+    assert_eq!(edit_distance(&chars("carne"), &chars("crane")), 1);
+    assert_eq!(edit_distance(&chars("cran"), &chars("crane")), 1);
+    assert_eq!(edit_distance(&chars("xxane"), &chars("crane")), 2);
+
+    let dict = Dictionary::load(&crate::source::Builtin::english()).unwrap();
+    let crane = Word::try_from("crane").unwrap();
+    assert_eq!(dict.nearest("crnae", 3)[0], crane);
+    assert!(dict.nearest("crne", 3).contains(&crane));
+    assert_eq!(dict.nearest("salte", 3)[0].to_string(), "slate");
+    assert!(dict.nearest("qxqxq", 3).is_empty());
+}
+
+#[test]
+fn test_daily_answer() {
+    let dict = Dictionary::load(&crate::source::Builtin::english()).unwrap();
+    let answer = |puzzle| dict.daily_answer(puzzle).to_string();
+    assert_eq!(answer(0), "cigar");
+    assert_eq!(answer(1), "rebut");
+    assert_eq!(answer(dict.answers.len() as u64), "cigar");
+}
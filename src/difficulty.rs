@@ -0,0 +1,85 @@
+use crate::dictionary::Alphabet;
+use crate::strategy::{frequency_score, letter_frequencies};
+use crate::Word;
+use anyhow::{anyhow, Result};
+use std::cmp::Reverse;
+use std::str::FromStr;
+
+/// How hard an answer is to find, for choosing random answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl FromStr for Difficulty {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(anyhow!(
+                "Unknown difficulty \"{}\" (use easy, medium or hard)",
+                s
+            )),
+        }
+    }
+}
+
+/// The answers of a difficulty, from splitting them into three equal tiers. Answers
+/// are ranked by how many guesses the player models took to find them on average,
+/// then by how rare their letters are, since uncommon words tend to be spelled with
+/// uncommon letters.
+pub fn tier(
+    answers: &[Word],
+    estimates: &[f64],
+    alphabet: &Alphabet,
+    difficulty: Difficulty,
+) -> Vec<Word> {
+    let freq = letter_frequencies(answers, alphabet);
+    let mut ranked: Vec<(f64, Reverse<i32>, Word)> = answers
+        .iter()
+        .zip(estimates)
+        .map(|(&answer, &guesses)| {
+            let score = frequency_score(&answer, alphabet, &freq);
+            (guesses, Reverse(score), answer)
+        })
+        .collect();
+    ranked.sort_by(|a, b| a.partial_cmp(b).expect("estimates aren't NaN"));
+
+    let tier = difficulty as usize;
+    let len = ranked.len();
+    ranked[tier * len / 3..(tier + 1) * len / 3]
+        .iter()
+        .map(|&(_, _, word)| word)
+        .collect()
+}
+
+#[test]
+fn test_tier() {
+    let matrix = crate::bench::test_matrix();
+    let alphabet = Alphabet::letters();
+    let tiers = |estimates: &[f64]| -> Vec<Vec<String>> {
+        let answers = &matrix.answers()[..estimates.len()];
+        [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard]
+            .iter()
+            .map(|&difficulty| {
+                tier(answers, estimates, &alphabet, difficulty)
+                    .iter()
+                    .map(|w| w.to_string())
+                    .collect()
+            })
+            .collect()
+    };
+    assert_eq!(
+        tiers(&[4.0, 1.0, 6.0, 2.0, 5.0, 3.0]),
+        [["rebut", "humph"], ["blush", "cigar"], ["awake", "sissy"]]
+    );
+    // Tiers that can't be equal still cover every answer once
+    let uneven = tiers(&[4.0, 1.0, 6.0, 2.0, 5.0]);
+    assert_eq!(uneven.concat().len(), 5);
+    assert_eq!(uneven[0], ["rebut"]);
+}
@@ -0,0 +1,52 @@
+use crate::i18n::Text;
+use rustyline::error::ReadlineError;
+use thiserror::Error;
+
+/// Why some text couldn't be read as a word. The messages follow what was being
+/// read, e.g. "The guess must be exactly 5 letters, not 4".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("{}", Text::WordError(*self))]
+pub enum WordError {
+    /// The number of letters that were given instead
+    Length(usize),
+    Digit(char),
+    /// A symbol that isn't part of the alphabet, or isn't part of any word
+    Symbol(char),
+}
+
+/// The ways reading words and results or playing a game can fail, for callers
+/// that handle some of them differently from the rest
+#[derive(Debug, Error)]
+pub enum WordleError {
+    #[error("\"{word}\" {reason}")]
+    InvalidWord { word: String, reason: WordError },
+    #[error("\"{0}\" isn't in the word list")]
+    NotInDictionary(String),
+    #[error("\"{0}\" isn't a valid result")]
+    InvalidResult(String),
+    /// A result that can't be true of any answer given an earlier one
+    #[error("\"{line}\" contradicts the earlier result \"{earlier}\" ({reason}). Use `drop` or `retract-guess` if the earlier one was the mistake.")]
+    ContradictoryHint {
+        line: String,
+        earlier: String,
+        reason: String,
+    },
+    /// The player quit with Ctrl-C or Ctrl-D
+    #[error("Interrupted")]
+    Interrupted,
+    #[error("{}", Text::OutOfGuesses)]
+    OutOfGuesses,
+    #[error(transparent)]
+    Readline(ReadlineError),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl From<ReadlineError> for WordleError {
+    fn from(e: ReadlineError) -> Self {
+        match e {
+            ReadlineError::Eof | ReadlineError::Interrupted => WordleError::Interrupted,
+            e => WordleError::Readline(e),
+        }
+    }
+}
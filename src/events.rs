@@ -0,0 +1,124 @@
+use crate::{GuessResult, Tile, Word};
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A stream of JSON objects, one per line, describing a game as it happens, for
+/// overlays and bots that want to follow along without parsing the terminal output.
+/// Every event has an `event` name and a `time` in milliseconds since the Unix epoch.
+pub struct Events(Option<Box<dyn Write>>);
+
+impl Events {
+    /// Events that go nowhere
+    pub fn none() -> Self {
+        Events(None)
+    }
+
+    /// Write events to an already open file descriptor, given as a number, or to a file
+    pub fn open(target: &str) -> Result<Self> {
+        let out: Box<dyn Write> = match target.parse::<i32>() {
+            Ok(fd) => Box::new(open_fd(fd)?),
+            Err(_) => Box::new(BufWriter::new(
+                File::create(target).with_context(|| format!("creating {}", target))?,
+            )),
+        };
+        Ok(Events(Some(out)))
+    }
+
+    pub fn guess_submitted(&mut self, number: usize, guess: Word) -> Result<()> {
+        self.emit(
+            "guess_submitted",
+            json!({ "number": number, "guess": guess.to_string() }),
+        )
+    }
+
+    pub fn feedback<L: Tile>(&mut self, guess: Word, result: &GuessResult<L>) -> Result<()> {
+        let tiles: Vec<&str> = result.0.iter().map(|tile| tile.name()).collect();
+        self.emit(
+            "feedback",
+            json!({ "guess": guess.to_string(), "tiles": tiles, "pattern": result.pattern() }),
+        )
+    }
+
+    /// The coach's grade for a guess, from A to F
+    pub fn guess_graded(&mut self, number: usize, guess: Word, grade: char) -> Result<()> {
+        self.emit(
+            "guess_graded",
+            json!({ "number": number, "guess": guess.to_string(), "grade": grade.to_string() }),
+        )
+    }
+
+    /// A result entered in the solver, as it was typed rather than as tiles, since
+    /// it can leave positions out
+    pub fn result_entered(&mut self, number: usize, result: &str) -> Result<()> {
+        self.emit(
+            "result_entered",
+            json!({ "number": number, "result": result }),
+        )
+    }
+
+    /// The solver's best suggestions, and how many words are still possible
+    pub fn suggestions(&mut self, suggestions: &[Word], candidates: usize) -> Result<()> {
+        let suggestions: Vec<String> = suggestions.iter().map(|w| w.to_string()).collect();
+        self.emit(
+            "suggestions",
+            json!({ "suggestions": suggestions, "candidates": candidates }),
+        )
+    }
+
+    /// `guesses` is `None` if the game wasn't solved, and `answer` is `None` if a
+    /// solve session ended without the answer being known
+    pub fn game_over(&mut self, answer: Option<Word>, guesses: Option<usize>) -> Result<()> {
+        let answer = answer.map(|answer| answer.to_string());
+        self.emit(
+            "game_over",
+            json!({ "answer": answer, "solved": guesses.is_some(), "guesses": guesses }),
+        )
+    }
+
+    fn emit(&mut self, event: &str, mut data: Value) -> Result<()> {
+        let out = match &mut self.0 {
+            Some(out) => out,
+            None => return Ok(()),
+        };
+        data["event"] = event.into();
+        data["time"] = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64)
+            .into();
+        // Flush each event, so they're seen as soon as they happen
+        writeln!(out, "{}", data)
+            .and_then(|_| out.flush())
+            .context("writing an event")
+    }
+}
+
+#[cfg(unix)]
+fn open_fd(fd: i32) -> Result<File> {
+    use std::os::unix::io::FromRawFd;
+    if fd <= 2 {
+        return Err(anyhow!(
+            "Events can't share stdin, stdout or stderr (fd {})",
+            fd
+        ));
+    }
+    // A closed descriptor's number could be handed out again later, e.g. to the
+    // history database, which the events would then be written over
+    // Safety: fcntl only looks the descriptor up, whether or not it's open
+    if unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+        return Err(anyhow!("File descriptor {} isn't open", fd));
+    }
+    // Safety: the descriptor was just checked to be open, and nothing else in this
+    // process uses it, so the file can own it and close it when it's dropped
+    Ok(unsafe { File::from_raw_fd(fd) })
+}
+
+#[cfg(not(unix))]
+fn open_fd(fd: i32) -> Result<File> {
+    Err(anyhow!(
+        "Events can only be written to a file descriptor ({}) on Unix",
+        fd
+    ))
+}
@@ -0,0 +1,203 @@
+use crate::dictionary::Dictionary;
+use crate::find::Query;
+use crate::reverse::parse_row;
+use crate::strategy::Frequencies;
+use crate::{guess_word, letters, solve, Word};
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use rustyline::error::ReadlineError;
+use std::collections::HashMap;
+
+/// The most words `list` shows, so a whole list doesn't flood the terminal
+const LIST_WORDS: usize = 100;
+
+/// How many words `sample` picks when it isn't told
+const SAMPLE_WORDS: usize = 10;
+
+const HELP: &str = "\
+Commands:
+  filter [contains=LETTERS] [exclude=LETTERS] [pattern=?A??E]
+                        keep the words matching, like the find command
+  result GUESS FEEDBACK keep the words that would give the feedback, e.g. \"result crane gy..b\"
+  list                  show the words
+  sample [N]            show N words at random
+  score WORD...         how well each word narrows down the words
+  partition WORD        show the groups a guess splits the words into
+  letters               show how common each letter is in the words
+  undo                  go back to the words before the last filter
+  reset                 start again from the whole list
+  quit                  leave";
+
+/// How well a guess narrows down a set of words
+#[derive(Debug, PartialEq)]
+pub struct Score {
+    /// How many different results the guess can get
+    pub groups: usize,
+    /// The number of words left after the guess, on average over the words
+    pub expected: f64,
+    /// The most words the guess can leave
+    pub worst: usize,
+}
+
+impl Score {
+    pub fn new(guess: Word, words: &[Word]) -> Self {
+        let mut groups: HashMap<u8, usize> = HashMap::new();
+        for &word in words {
+            *groups.entry(guess_word(guess, word).pattern()).or_default() += 1;
+        }
+        let squares: usize = groups.values().map(|size| size * size).sum();
+        Score {
+            groups: groups.len(),
+            expected: squares as f64 / words.len().max(1) as f64,
+            worst: groups.values().copied().max().unwrap_or(0),
+        }
+    }
+}
+
+/// Filter, sample, score and partition the word lists interactively, for studying
+/// the game rather than playing it. Nothing is recorded.
+pub fn explore(dict: &Dictionary, more_words: bool) -> Result<()> {
+    let all = dict.words(more_words);
+    // Each filter pushes the words it left, so they can be undone
+    let mut stack = vec![all];
+    println!("{} words. Type \"help\" for the commands.", stack[0].len());
+    let mut rl = rustyline::Editor::<()>::new();
+    loop {
+        let words = stack.last().expect("the whole list is never popped");
+        let line = match rl.readline(&format!("{}> ", words.len())) {
+            Ok(line) => line,
+            Err(ReadlineError::Eof | ReadlineError::Interrupted) => return Ok(()),
+            Err(e) => return Err(anyhow!("Error: {}", e)),
+        };
+        rl.add_history_entry(line.as_str());
+        let args: Vec<&str> = line.split_whitespace().collect();
+        let (command, args) = match args.split_first() {
+            Some((command, args)) => (*command, args),
+            None => continue,
+        };
+        let filtered = match (command, args) {
+            ("filter", _) => filter(dict, words, args),
+            ("result", _) => result_filter(dict, words, args),
+            ("undo", []) if stack.len() > 1 => {
+                stack.pop();
+                continue;
+            }
+            ("undo", []) => {
+                eprintln!("Nothing to undo");
+                continue;
+            }
+            ("reset", []) => {
+                stack.truncate(1);
+                continue;
+            }
+            ("quit", _) => return Ok(()),
+            _ => {
+                if let Err(e) = run(dict, words, command, args) {
+                    eprintln!("{}", e);
+                }
+                continue;
+            }
+        };
+        match filtered {
+            Ok(new) => {
+                println!("{} of the {} words are left.", new.len(), words.len());
+                stack.push(new);
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+/// Carry out a command that doesn't filter the words
+fn run(dict: &Dictionary, words: &[Word], command: &str, args: &[&str]) -> Result<()> {
+    match (command, args) {
+        ("help", _) => println!("{}", HELP),
+        ("list", []) => {
+            let mut listed = words.iter().take(LIST_WORDS).join(" ");
+            if words.len() > LIST_WORDS {
+                listed += &format!(" (+{} more)", words.len() - LIST_WORDS);
+            }
+            println!("{}", listed);
+        }
+        ("sample", _) => {
+            let count = match args {
+                [] => SAMPLE_WORDS,
+                [count] => count
+                    .parse()
+                    .map_err(|_| anyhow!("\"{}\" isn't a number", count))?,
+                _ => return Err(anyhow!("Usage: sample [N]")),
+            };
+            let sample = words.choose_multiple(&mut rand::thread_rng(), count);
+            println!("{}", sample.sorted().join(" "));
+        }
+        ("score", [_, ..]) => {
+            for word in args {
+                let guess = dict.alphabet.parse_word(word)?;
+                let score = Score::new(guess, words);
+                println!(
+                    "{}: {} groups, {:.1} words left on average, {} at worst",
+                    guess, score.groups, score.expected, score.worst
+                );
+            }
+        }
+        ("partition", [word]) => {
+            let guess = dict.alphabet.parse_word(word)?;
+            solve::display_partition(guess, words, guess_word);
+        }
+        ("letters", []) => {
+            let freq = Frequencies::count(words, &dict.alphabet);
+            letters::display_letters(words.len(), &freq, &dict.alphabet, false)
+        }
+        ("score", _) => return Err(anyhow!("Usage: score WORD...")),
+        ("partition", _) => return Err(anyhow!("Usage: partition WORD")),
+        _ => return Err(anyhow!("Unknown command \"{}\" (try help)", command)),
+    }
+    Ok(())
+}
+
+/// The words matching `filter`'s `name=value` arguments
+fn filter(dict: &Dictionary, words: &[Word], args: &[&str]) -> Result<Vec<Word>> {
+    let (mut contains, mut exclude, mut pattern) = (None, None, None);
+    for arg in args {
+        match arg.split_once('=') {
+            Some(("contains", value)) => contains = Some(value),
+            Some(("exclude", value)) => exclude = Some(value),
+            Some(("pattern", value)) => pattern = Some(value),
+            _ => {
+                return Err(anyhow!(
+                    "Usage: filter [contains=LETTERS] [exclude=LETTERS] [pattern=?A??E]"
+                ))
+            }
+        }
+    }
+    let query = Query::new(&dict.alphabet, contains, exclude, pattern)?;
+    Ok(words.iter().filter(|w| query.matches(w)).copied().collect())
+}
+
+/// The words that would give a guess the feedback
+fn result_filter(dict: &Dictionary, words: &[Word], args: &[&str]) -> Result<Vec<Word>> {
+    let (guess, feedback) = match args {
+        [guess, feedback] => (*guess, *feedback),
+        _ => return Err(anyhow!("Usage: result GUESS FEEDBACK")),
+    };
+    let guess = dict.alphabet.parse_word(guess)?;
+    let pattern = parse_row(feedback)?;
+    Ok(words
+        .iter()
+        .filter(|&&w| guess_word(guess, w).pattern() == pattern)
+        .copied()
+        .collect())
+}
+
+#[test]
+fn test_score() {
+    use crate::bench::test_matrix;
+    let matrix = test_matrix();
+    let words = matrix.answers();
+    // Every word gets a different result from "blush" except "cigar" and "awake"
+    let score = Score::new("blush".try_into().unwrap(), words);
+    assert_eq!(score.worst, 2);
+    assert_eq!(score.groups, 5);
+    assert!((score.expected - 8.0 / 6.0).abs() < 1e-9);
+}
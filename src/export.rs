@@ -0,0 +1,96 @@
+use crate::bench::csv_field;
+use crate::stats::Profile;
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use serde_json::json;
+use std::io::Write;
+use std::str::FromStr;
+
+/// How to write out the history for `export`
+#[derive(Debug, Clone, Copy)]
+pub enum Format {
+    /// One row per game or solve session, with a `kind` column telling which
+    Csv,
+    /// An object with a list of games and a list of solve sessions
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(anyhow!("Unknown format \"{}\" (use csv or json)", s)),
+        }
+    }
+}
+
+/// Write every game and solve session of the profiles
+pub fn export(profiles: &[Profile], format: Format, mut out: impl Write) -> Result<()> {
+    match format {
+        Format::Csv => {
+            writeln!(
+                out,
+                "kind,profile,time,mode,answer,guesses,elapsed,results,candidates"
+            )?;
+            for profile in profiles {
+                let name = csv_field(&profile.name);
+                for game in profile.history()? {
+                    writeln!(
+                        out,
+                        "game,{},{},{},{},{},{:.1},,",
+                        name,
+                        game.time,
+                        csv_field(&game.mode),
+                        csv_field(&game.answer.to_string()),
+                        game.guesses.map_or(String::new(), |g| g.to_string()),
+                        game.elapsed.as_secs_f64()
+                    )?;
+                }
+                for session in profile.sessions()? {
+                    writeln!(
+                        out,
+                        "session,{},{},{},{},,,{},{}",
+                        name,
+                        session.time,
+                        csv_field(&session.variant),
+                        csv_field(&session.answer.map_or(String::new(), |w| w.to_string())),
+                        csv_field(&session.results.join(" ")),
+                        session.candidates.iter().join(" ")
+                    )?;
+                }
+            }
+        }
+        Format::Json => {
+            let (mut games, mut sessions) = (vec![], vec![]);
+            for profile in profiles {
+                for game in profile.history()? {
+                    games.push(json!({
+                        "profile": profile.name,
+                        "time": game.time,
+                        "mode": game.mode,
+                        "answer": game.answer.to_string(),
+                        "guesses": game.guesses,
+                        "elapsed": game.elapsed.as_secs_f64(),
+                    }));
+                }
+                for session in profile.sessions()? {
+                    sessions.push(json!({
+                        "profile": profile.name,
+                        "time": session.time,
+                        "variant": session.variant,
+                        "answer": session.answer.map(|w| w.to_string()),
+                        "results": session.results,
+                        "candidates": session.candidates,
+                    }));
+                }
+            }
+            let history = json!({ "games": games, "sessions": sessions });
+            writeln!(out, "{:#}", history)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use crate::dictionary::Alphabet;
+use crate::Word;
+use anyhow::{anyhow, Result};
+
+/// Filters for looking words up outside of a solve session, like a crossword helper
+#[derive(Debug, Default)]
+pub struct Query {
+    /// Letters the word has to have, as many times as they're listed
+    pub contains: Vec<char>,
+    /// Letters the word can't have
+    pub exclude: Vec<char>,
+    /// The symbol in each position, where one is required
+    pub pattern: [Option<char>; 5],
+}
+
+impl Query {
+    pub fn new(
+        alphabet: &Alphabet,
+        contains: Option<&str>,
+        exclude: Option<&str>,
+        pattern: Option<&str>,
+    ) -> Result<Self> {
+        let mut query = Query::default();
+        if let Some(contains) = contains {
+            query.contains = symbols(alphabet, contains)?;
+        }
+        if let Some(exclude) = exclude {
+            query.exclude = symbols(alphabet, exclude)?;
+        }
+        if let Some(c) = query.exclude.iter().find(|c| query.contains.contains(c)) {
+            return Err(anyhow!("'{}' can't be both contained and excluded", c));
+        }
+        if let Some(pattern) = pattern {
+            // `?` or `.` leave a position open
+            let chars: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+            if chars.len() != 5 {
+                return Err(anyhow!(
+                    "The pattern \"{}\" should be 5 symbols long",
+                    pattern
+                ));
+            }
+            for (position, &c) in chars.iter().enumerate() {
+                query.pattern[position] = match c {
+                    '?' | '.' => None,
+                    c if alphabet.contains(c) => Some(c),
+                    c => return Err(anyhow!("'{}' isn't in the alphabet", c)),
+                };
+            }
+        }
+        Ok(query)
+    }
+
+    pub fn matches(&self, word: &Word) -> bool {
+        let count = |c: char| word.0.iter().filter(|&&w| w == c).count();
+        self.pattern
+            .iter()
+            .zip(word.0)
+            .all(|(p, c)| p.is_none_or(|p| p == c))
+            && self
+                .contains
+                .iter()
+                .all(|&c| count(c) >= self.contains.iter().filter(|&&d| d == c).count())
+            && self.exclude.iter().all(|&c| count(c) == 0)
+    }
+}
+
+fn symbols(alphabet: &Alphabet, letters: &str) -> Result<Vec<char>> {
+    let chars: Vec<char> = letters.chars().flat_map(char::to_lowercase).collect();
+    match chars.iter().find(|&&c| !alphabet.contains(c)) {
+        Some(c) => Err(anyhow!("'{}' isn't in the alphabet", c)),
+        None => Ok(chars),
+    }
+}
+
+#[test]
+fn test_query() {
+    let alphabet = Alphabet::letters();
+    let word = |w: &str| -> Word { w.try_into().unwrap() };
+    let query = Query::new(&alphabet, Some("aeis"), Some("t"), Some("?a??e")).unwrap();
+    assert!(query.matches(&word("raise")));
+    assert!(!query.matches(&word("arise")));
+    assert!(!query.matches(&word("saint")));
+
+    let query = Query::new(&alphabet, Some("ee"), None, None).unwrap();
+    assert!(query.matches(&word("geese")));
+    assert!(!query.matches(&word("crane")));
+
+    assert!(Query::new(&alphabet, Some("a"), Some("a"), None).is_err());
+    assert!(Query::new(&alphabet, None, None, Some("??")).is_err());
+}
@@ -0,0 +1,140 @@
+use crate::dictionary::Dictionary;
+use crate::i18n::Text;
+use crate::{GuessResult, Tile, Word, WordError, MAX_GUESSES};
+use std::fmt;
+
+/// Why a guess wasn't taken
+#[derive(Debug)]
+pub enum GuessError {
+    Word(WordError),
+    NotInList,
+    Over,
+}
+
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GuessError::Word(e) => write!(f, "{}", Text::GuessWordError(*e)),
+            GuessError::NotInList => write!(f, "{}", Text::NotInList),
+            GuessError::Over => write!(f, "{}", Text::GameOver),
+        }
+    }
+}
+
+/// The state of a game being played, shared by the terminal and the window
+pub struct Game<L> {
+    answer: Word,
+    feedback: fn(Word, Word) -> GuessResult<L>,
+    guesses: Vec<Word>,
+    results: Vec<GuessResult<L>>,
+    /// The positions whose letters have been given away as hints
+    hints: Vec<usize>,
+}
+
+impl<L: Tile> Game<L> {
+    pub fn new(answer: Word, feedback: fn(Word, Word) -> GuessResult<L>) -> Self {
+        Game {
+            answer,
+            feedback,
+            guesses: vec![],
+            results: vec![],
+            hints: vec![],
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn answer(&self) -> Word {
+        self.answer
+    }
+
+    pub fn guesses(&self) -> &[Word] {
+        &self.guesses
+    }
+
+    pub fn results(&self) -> &[GuessResult<L>] {
+        &self.results
+    }
+
+    /// Check the input is a guess the game accepts, and if so, make it
+    #[cfg(any(feature = "gui", test))]
+    pub fn guess(&mut self, dict: &Dictionary, input: &str) -> Result<Word, GuessError> {
+        let guess = self.check(dict, input)?;
+        self.submit(guess);
+        Ok(guess)
+    }
+
+    /// Check the input is a guess the game accepts, without making it yet
+    pub fn check(&self, dict: &Dictionary, input: &str) -> Result<Word, GuessError> {
+        if self.is_over() {
+            return Err(GuessError::Over);
+        }
+        let guess = dict.alphabet.check_word(input).map_err(GuessError::Word)?;
+        if !dict.is_accepted(&guess) {
+            return Err(GuessError::NotInList);
+        }
+        Ok(guess)
+    }
+
+    /// Make a guess that `check` accepted
+    pub fn submit(&mut self, guess: Word) {
+        self.guesses.push(guess);
+        self.results.push((self.feedback)(guess, self.answer));
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.guesses.last() == Some(&self.answer)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.guesses.len() >= MAX_GUESSES
+    }
+
+    pub fn hints(&self) -> usize {
+        self.hints.len()
+    }
+
+    /// Give away the letter in one position of the answer, picking whichever of the
+    /// positions not yet known rules out the fewest candidates, so it spoils as
+    /// little as possible. Returns `None` if every position is known.
+    pub fn hint(&mut self, dict: &Dictionary) -> Option<(usize, char)> {
+        let known: Vec<usize> = (0..5)
+            .filter(|&i| self.hints.contains(&i) || self.results.iter().any(|r| r.0[i].code() == 2))
+            .collect();
+        let candidates: Vec<&Word> = dict
+            .answers
+            .iter()
+            .filter(|word| self.is_consistent(word))
+            .collect();
+        let position = (0..5).filter(|i| !known.contains(i)).max_by_key(|&i| {
+            let kept = candidates
+                .iter()
+                .filter(|word| word.0[i] == self.answer.0[i])
+                .count();
+            // Ties go to the earliest position
+            (kept, std::cmp::Reverse(i))
+        })?;
+        self.hints.push(position);
+        Some((position, self.answer.0[position]))
+    }
+
+    /// Whether the word could still be the answer, given the results so far
+    pub fn is_consistent(&self, word: &Word) -> bool {
+        self.guesses
+            .iter()
+            .zip(&self.results)
+            .all(|(&guess, result)| (self.feedback)(guess, *word).pattern() == result.pattern())
+    }
+}
+
+#[test]
+fn test_hint() {
+    use crate::guess_word;
+    let dict = Dictionary::load(&crate::source::Builtin::english()).unwrap();
+    let mut game = Game::new("crane".try_into().unwrap(), guess_word);
+    game.guess(&dict, "trace").unwrap();
+    // The yellow c can only go first, so giving it away rules nothing out
+    assert_eq!(game.hint(&dict), Some((0, 'c')));
+    assert_eq!(game.hint(&dict), Some((3, 'n')));
+    assert_eq!(game.hint(&dict), None);
+    assert_eq!(game.hints(), 2);
+}
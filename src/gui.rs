@@ -0,0 +1,228 @@
+use crate::dictionary::Dictionary;
+use crate::game::Game;
+use crate::i18n::Text;
+use crate::solve::suggest;
+use crate::{key_states, GuessLetter, GuessResult, Tile, Word, MAX_GUESSES};
+use anyhow::{anyhow, Result};
+use eframe::egui::{self, Align2, Button, Color32, FontId, Key, RichText, Sense, Stroke, Ui, Vec2};
+
+const TILE_SIZE: f32 = 56.0;
+const KEY_SIZE: Vec2 = Vec2::new(34.0, 44.0);
+const KEYS_PER_ROW: usize = 10;
+const SUGGESTIONS: usize = 10;
+
+/// Play in a window, with a clickable keyboard and the solver's suggestions alongside
+pub fn run<L: Tile + 'static>(
+    dict: Dictionary,
+    feedback: fn(Word, Word) -> GuessResult<L>,
+) -> Result<()> {
+    eframe::run_native(
+        "Wordle Solve",
+        eframe::NativeOptions::default(),
+        Box::new(move |_| Ok(Box::new(App::new(dict, feedback)))),
+    )
+    .map_err(|e| anyhow!("{}", e))
+}
+
+struct App<L> {
+    dict: Dictionary,
+    feedback: fn(Word, Word) -> GuessResult<L>,
+    game: Game<L>,
+    /// The letters typed for the next guess
+    input: String,
+    message: String,
+    show_suggestions: bool,
+}
+
+impl<L: Tile> App<L> {
+    fn new(dict: Dictionary, feedback: fn(Word, Word) -> GuessResult<L>) -> Self {
+        let game = Game::new(dict.random_answer(), feedback);
+        App {
+            dict,
+            feedback,
+            game,
+            input: String::new(),
+            message: String::new(),
+            show_suggestions: false,
+        }
+    }
+
+    fn new_game(&mut self) {
+        self.game = Game::new(self.dict.random_answer(), self.feedback);
+        self.input.clear();
+        self.message.clear();
+    }
+
+    fn type_symbol(&mut self, c: char) {
+        let c = c.to_ascii_lowercase();
+        if !self.game.is_over() && self.input.chars().count() < 5 && self.dict.alphabet.contains(c)
+        {
+            self.input.push(c);
+        }
+    }
+
+    fn submit(&mut self) {
+        match self.game.guess(&self.dict, &self.input) {
+            Ok(_) => {
+                self.input.clear();
+                self.message = if self.game.is_won() {
+                    Text::GoodJob(self.game.guesses().len()).to_string()
+                } else if self.game.is_over() {
+                    Text::BetterLuck(self.game.answer()).to_string()
+                } else {
+                    String::new()
+                };
+            }
+            Err(e) => self.message = format!("{}.", e),
+        }
+    }
+
+    fn handle_keys(&mut self, ctx: &egui::Context) {
+        let events = ctx.input(|i| i.events.clone());
+        for event in events {
+            match event {
+                egui::Event::Text(text) => text.chars().for_each(|c| self.type_symbol(c)),
+                egui::Event::Key {
+                    key, pressed: true, ..
+                } => match key {
+                    Key::Enter => self.submit(),
+                    Key::Backspace => {
+                        self.input.pop();
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+    }
+
+    fn board(&self, ui: &mut Ui) {
+        for row in 0..MAX_GUESSES {
+            ui.horizontal(|ui| {
+                for col in 0..5 {
+                    let (letter, tile) = match self.game.guesses().get(row) {
+                        Some(guess) => (Some(guess.0[col]), Some(&self.game.results()[row].0[col])),
+                        None if row == self.game.guesses().len() => {
+                            (self.input.chars().nth(col), None)
+                        }
+                        None => (None, None),
+                    };
+                    draw_tile(ui, letter, tile.map(Tile::name));
+                }
+            });
+        }
+    }
+
+    fn keyboard(&mut self, ui: &mut Ui) {
+        let keys = key_states(&self.dict.alphabet, self.game.results());
+        let symbols: Vec<(char, GuessLetter)> = self
+            .dict
+            .alphabet
+            .symbols()
+            .iter()
+            .copied()
+            .zip(keys)
+            .collect();
+        for row in symbols.chunks(KEYS_PER_ROW) {
+            ui.horizontal(|ui| {
+                for &(c, key) in row {
+                    let fill = match key {
+                        GuessLetter::Empty => None,
+                        key => Some(tile_color(key.name())),
+                    };
+                    let mut button = Button::new(
+                        RichText::new(c.to_ascii_uppercase().to_string()).color(Color32::WHITE),
+                    )
+                    .min_size(KEY_SIZE);
+                    if let Some(fill) = fill {
+                        button = button.fill(fill);
+                    }
+                    if ui.add(button).clicked() {
+                        self.type_symbol(c);
+                    }
+                }
+            });
+        }
+        ui.horizontal(|ui| {
+            if ui.add(Button::new("Enter").min_size(KEY_SIZE)).clicked() {
+                self.submit();
+            }
+            if ui.add(Button::new("⌫").min_size(KEY_SIZE)).clicked() {
+                self.input.pop();
+            }
+        });
+    }
+
+    fn suggestions(&self, ui: &mut Ui) {
+        let game = &self.game;
+        let freq = self.dict.frequencies(false);
+        let (candidates, scores) = suggest(
+            &self.dict.answers,
+            &self.dict.alphabet,
+            Some(&freq.letters),
+            |word| game.is_consistent(word),
+        );
+        ui.heading("Suggestions");
+        ui.label(format!("{} possible answers", candidates.len()));
+        for word in scores.values().flatten().take(SUGGESTIONS) {
+            ui.monospace(word.to_string());
+        }
+    }
+}
+
+impl<L: Tile> eframe::App for App<L> {
+    fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame) {
+        self.handle_keys(ctx);
+
+        if self.show_suggestions {
+            egui::SidePanel::right("suggestions").show(ctx, |ui| self.suggestions(ui));
+        }
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("New game").clicked() {
+                    self.new_game();
+                }
+                ui.checkbox(&mut self.show_suggestions, "Show suggestions");
+            });
+            ui.separator();
+            self.board(ui);
+            ui.label(&self.message);
+            ui.separator();
+            self.keyboard(ui);
+        });
+    }
+}
+
+/// Draw one square of the board, colored by what it says about its letter
+fn draw_tile(ui: &mut Ui, letter: Option<char>, state: Option<&str>) {
+    let (rect, _) = ui.allocate_exact_size(Vec2::splat(TILE_SIZE), Sense::hover());
+    let painter = ui.painter();
+    match state {
+        Some(state) => {
+            painter.rect_filled(rect, 2.0, tile_color(state));
+        }
+        None => {
+            painter.rect_stroke(rect, 2.0, Stroke::new(2.0, Color32::DARK_GRAY));
+        }
+    }
+    if let Some(c) = letter {
+        painter.text(
+            rect.center(),
+            Align2::CENTER_CENTER,
+            c.to_ascii_uppercase(),
+            FontId::proportional(TILE_SIZE / 2.0),
+            Color32::WHITE,
+        );
+    }
+}
+
+/// The colors of the real game's tiles
+fn tile_color(state: &str) -> Color32 {
+    match state {
+        "correct" => Color32::from_rgb(0x53, 0x8d, 0x4e),
+        "present" | "earlier" => Color32::from_rgb(0xb5, 0x9f, 0x3b),
+        "later" => Color32::from_rgb(0x3a, 0x6e, 0xb5),
+        "absent" => Color32::from_rgb(0x3a, 0x3a, 0x3c),
+        _ => Color32::TRANSPARENT,
+    }
+}
@@ -0,0 +1,55 @@
+use crate::stats::GameRecord;
+use crate::MAX_GUESSES;
+use anyhow::{anyhow, Result};
+use std::str::FromStr;
+
+/// The fewest games a profile needs before its average says how strong a player it is
+const MIN_GAMES: usize = 3;
+
+/// What the weaker side of a race gets, to keep mixed-skill races close
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handicap {
+    /// A guess's head start: their result counts one guess fewer
+    Guess,
+    /// One of the answer's letters, and where it goes
+    Letter,
+}
+
+impl FromStr for Handicap {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "guess" => Ok(Handicap::Guess),
+            "letter" => Ok(Handicap::Letter),
+            _ => Err(anyhow!("Unknown handicap \"{}\" (use guess or letter)", s)),
+        }
+    }
+}
+
+/// The mean number of guesses a player's games took, counting a game that wasn't
+/// solved as one more than allowed, or `None` if there are too few games to tell
+pub fn mean_guesses(games: &[GameRecord]) -> Option<f64> {
+    if games.len() < MIN_GAMES {
+        return None;
+    }
+    let total: usize = games
+        .iter()
+        .map(|game| game.guesses.unwrap_or(MAX_GUESSES + 1))
+        .sum();
+    Some(total as f64 / games.len() as f64)
+}
+
+#[test]
+fn test_mean_guesses() {
+    use std::time::Duration;
+    let answer = "crane".try_into().unwrap();
+    let game = |guesses| GameRecord::new("random", answer, guesses, Duration::ZERO);
+    assert_eq!(mean_guesses(&[game(Some(3)), game(Some(4))]), None);
+    assert_eq!(
+        mean_guesses(&[game(Some(3)), game(Some(4)), game(None)]),
+        Some(14.0 / 3.0)
+    );
+    assert!("letter".parse::<Handicap>().is_ok());
+    assert!("extra".parse::<Handicap>().is_err());
+}
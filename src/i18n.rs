@@ -0,0 +1,413 @@
+use crate::{Word, WordError};
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+/// A language the messages of a game can be shown in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    English,
+    Spanish,
+}
+
+impl FromStr for Lang {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "en" => Ok(Lang::English),
+            "es" => Ok(Lang::Spanish),
+            _ => Err(anyhow!("Unknown language \"{}\" (use en or es)", s)),
+        }
+    }
+}
+
+impl Lang {
+    /// The language chosen with `--ui-lang`, or English if there wasn't one
+    pub fn current() -> Lang {
+        *LANG.get_or_init(|| Lang::English)
+    }
+
+    /// Show messages in this language from then on
+    pub fn set(self) -> Result<()> {
+        LANG.set(self)
+            .map_err(|_| anyhow!("The language has already been chosen"))
+    }
+}
+
+/// A message shown while playing, written in the current language when displayed
+pub enum Text<'a> {
+    GuessPrompt(usize),
+    Correct(Word),
+    GoodJob(usize),
+    BetterLuck(Word),
+    OutOfGuesses,
+    WordError(WordError),
+    GuessWordError(WordError),
+    NotInList,
+    GameOver,
+    DidYouMean(&'a [Word]),
+    PressEnter(Word),
+    ConfirmGuess(Word),
+    Hint(usize, char),
+    NoHint,
+    HintScore(usize, f64),
+    NextPuzzle(u64),
+    SolverGuessed(&'a [Word]),
+    BeatSolver,
+    Tie,
+    NeitherSolved,
+    SolverWins(usize),
+    WorkingOutHandicap,
+    NoHandicap(&'a str),
+    /// The player's and the solver's average guesses
+    GuessHeadStart(f64, f64),
+    /// The averages, and the letter given away with its position
+    LetterHeadStart(f64, f64, char, usize),
+    SolverHeadStart(f64, f64),
+    CoachSummary(&'a [char]),
+    /// How many answers were possible before and after the guess
+    CoachNarrowed(usize, usize),
+    /// The grade, with the bits the guess and the best guess find out
+    CoachGrade(char, f64, f64),
+    CoachSameGuess,
+    /// The answers the guess leaves on average, and the solver's guess and its average
+    CoachCompare(f64, Word, f64),
+    HostRound(usize, usize),
+    ScoreSoFar(usize),
+    FinalScore(usize, &'a [usize]),
+    EnterSecrets,
+    SecretPrompt(usize),
+    SecretError(WordError),
+    EnterPlayers,
+    PlayerPrompt(usize),
+    TooFewPlayers,
+    SamePlayer(&'a str),
+    RelayWinner(&'a str),
+    RevealIn(u64),
+    NoHintStreaming,
+}
+
+impl fmt::Display for Text<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match Lang::current() {
+            Lang::English => self.english(f),
+            Lang::Spanish => self.spanish(f),
+        }
+    }
+}
+
+impl Text<'_> {
+    fn english(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Text::GuessPrompt(n) => write!(f, "Guess {} of {}: ", n, crate::MAX_GUESSES),
+            Text::Correct(answer) => write!(f, "Correct!  It was \"{}\"", answer),
+            Text::GoodJob(guesses) => write!(f, "Good job! It took you {} guesses", guesses),
+            Text::BetterLuck(answer) => {
+                write!(f, "Better luck next time.  The answer was \"{}\".", answer)
+            }
+            Text::OutOfGuesses => write!(f, "Ran out of guesses"),
+            Text::WordError(e) => match e {
+                WordError::Length(0) => write!(f, "is empty"),
+                WordError::Length(len) => write!(f, "must be exactly 5 letters, not {}", len),
+                WordError::Digit(c) => write!(f, "contains a digit ('{}')", c),
+                WordError::Symbol(c) if c.is_whitespace() => write!(f, "contains a space"),
+                WordError::Symbol(c) => {
+                    write!(f, "contains '{}', which isn't in the alphabet", c)
+                }
+            },
+            Text::GuessWordError(e) => write!(f, "The guess {}", Text::WordError(e)),
+            Text::NotInList => write!(f, "Not in word list"),
+            Text::GameOver => write!(f, "The game is over"),
+            Text::DidYouMean(words) => write!(f, "Did you mean {}?", quoted(words, "or")),
+            Text::PressEnter(word) => write!(f, "Press Enter to guess \"{}\".", word),
+            Text::ConfirmGuess(word) => write!(f, "Lock in \"{}\"? [Y/n] ", word),
+            Text::Hint(position, letter) => write!(
+                f,
+                "Position {} is '{}'. Each hint counts as half a guess.",
+                position + 1,
+                letter.to_ascii_uppercase()
+            ),
+            Text::NoHint => write!(f, "Every letter is already known."),
+            Text::HintScore(1, score) => {
+                write!(f, "With a hint at half a guess, that scores {}.", score)
+            }
+            Text::HintScore(hints, score) => write!(
+                f,
+                "With {} hints at half a guess each, that scores {}.",
+                hints, score
+            ),
+            Text::NextPuzzle(secs) => write!(
+                f,
+                "The next puzzle is in {}h {:02}m.",
+                secs / 3600,
+                secs % 3600 / 60
+            ),
+            Text::SolverGuessed(words) => {
+                write!(f, "The solver guessed {}", words.iter().join(", "))
+            }
+            Text::BeatSolver => write!(f, "You beat the solver!"),
+            Text::Tie => write!(f, "It's a tie."),
+            Text::NeitherSolved => write!(f, "Neither of you solved it."),
+            Text::SolverWins(guesses) => write!(f, "The solver wins in {} guesses.", guesses),
+            Text::WorkingOutHandicap => {
+                write!(f, "Working out the solver's average to set the handicap.")
+            }
+            Text::NoHandicap(name) => write!(
+                f,
+                "{} hasn't played enough games to work out a handicap.",
+                name
+            ),
+            Text::GuessHeadStart(player, solver) => write!(
+                f,
+                "You average {:.2} guesses to the solver's {:.2}, so you get a guess's head start.",
+                player, solver
+            ),
+            Text::LetterHeadStart(player, solver, letter, position) => write!(
+                f,
+                "You average {:.2} guesses to the solver's {:.2}, so here's a letter: the answer has '{}' in position {}.",
+                player,
+                solver,
+                letter,
+                position + 1
+            ),
+            Text::SolverHeadStart(player, solver) => write!(
+                f,
+                "You average {:.2} guesses to the solver's {:.2}, so the solver gets a guess's head start.",
+                player, solver
+            ),
+            Text::CoachSummary(grades) => write!(
+                f,
+                "Coach: Your guesses were graded {}.",
+                grades.iter().join(", ")
+            ),
+            Text::CoachNarrowed(before, after) => write!(
+                f,
+                "Coach: {} possible answers before that guess, {} after.",
+                before, after
+            ),
+            Text::CoachGrade(grade, information, best) => write!(
+                f,
+                "Coach: Grade {}, finding {:.2} bits of the {:.2} the best guess could.",
+                grade, information, best
+            ),
+            Text::CoachSameGuess => write!(f, "Coach: That's the guess the solver would have made."),
+            Text::CoachCompare(yours, pick, solvers) => write!(
+                f,
+                "Coach: Yours leaves {:.1} on average. The solver would have guessed \"{}\", which leaves {:.1}, so yours was {}.",
+                yours,
+                pick,
+                solvers,
+                if yours < solvers {
+                    "better"
+                } else if yours > solvers {
+                    "worse"
+                } else {
+                    "just as good"
+                }
+            ),
+            Text::HostRound(n, total) => write!(f, "Word {} of {}", n, total),
+            Text::ScoreSoFar(score) => write!(f, "Score so far: {}", score),
+            Text::FinalScore(total, scores) => {
+                write!(f, "Final score: {} ({})", total, scores_list(scores))
+            }
+            Text::EnterSecrets => write!(
+                f,
+                "Enter the secret words, then a blank line to start the game."
+            ),
+            Text::SecretPrompt(n) => write!(f, "Secret word {}: ", n),
+            Text::SecretError(e) => write!(f, "The word {}, try again.", Text::WordError(e)),
+            Text::EnterPlayers => write!(
+                f,
+                "Enter each player's name in the order you'll guess, then a blank line to start."
+            ),
+            Text::PlayerPrompt(n) => write!(f, "Player {}: ", n),
+            Text::TooFewPlayers => write!(f, "A relay needs at least 2 players."),
+            Text::SamePlayer(name) => write!(f, "{} is already playing.", name),
+            Text::RelayWinner(name) => write!(f, "{} made the winning guess!", name),
+            Text::RevealIn(secs) => write!(f, "Revealing the answer in {}s ", secs),
+            Text::NoHintStreaming => write!(
+                f,
+                "Hints are off while streaming, since they'd show a letter of the answer."
+            ),
+        }
+    }
+
+    fn spanish(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Text::GuessPrompt(n) => write!(f, "Intento {} de {}: ", n, crate::MAX_GUESSES),
+            Text::Correct(answer) => write!(f, "¡Correcto!  Era «{}»", answer),
+            Text::GoodJob(guesses) => {
+                write!(f, "¡Bien hecho! Te ha llevado {} intentos", guesses)
+            }
+            Text::BetterLuck(answer) => {
+                write!(
+                    f,
+                    "Más suerte la próxima vez.  La respuesta era «{}».",
+                    answer
+                )
+            }
+            Text::OutOfGuesses => write!(f, "Se acabaron los intentos"),
+            Text::WordError(e) => match e {
+                WordError::Length(0) => write!(f, "está vacía"),
+                WordError::Length(len) => {
+                    write!(f, "debe tener exactamente 5 letras, no {}", len)
+                }
+                WordError::Digit(c) => write!(f, "contiene un dígito ('{}')", c),
+                WordError::Symbol(c) if c.is_whitespace() => write!(f, "contiene un espacio"),
+                WordError::Symbol(c) => {
+                    write!(f, "contiene '{}', que no está en el alfabeto", c)
+                }
+            },
+            Text::GuessWordError(e) => write!(f, "La palabra {}", Text::WordError(e)),
+            Text::NotInList => write!(f, "No está en la lista de palabras"),
+            Text::GameOver => write!(f, "La partida ha terminado"),
+            Text::DidYouMean(words) => write!(f, "¿Quisiste decir {}?", quoted(words, "o")),
+            Text::PressEnter(word) => write!(f, "Pulsa Intro para probar «{}».", word),
+            Text::ConfirmGuess(word) => write!(f, "¿Confirmas «{}»? [S/n] ", word),
+            Text::Hint(position, letter) => write!(
+                f,
+                "La posición {} es '{}'. Cada pista cuenta como medio intento.",
+                position + 1,
+                letter.to_ascii_uppercase()
+            ),
+            Text::NoHint => write!(f, "Ya se conocen todas las letras."),
+            Text::HintScore(1, score) => {
+                write!(
+                    f,
+                    "Con una pista de medio intento, la puntuación es {}.",
+                    score
+                )
+            }
+            Text::HintScore(hints, score) => write!(
+                f,
+                "Con {} pistas de medio intento cada una, la puntuación es {}.",
+                hints, score
+            ),
+            Text::NextPuzzle(secs) => write!(
+                f,
+                "El próximo reto es dentro de {}h {:02}m.",
+                secs / 3600,
+                secs % 3600 / 60
+            ),
+            Text::SolverGuessed(words) => {
+                write!(f, "El solucionador probó {}", words.iter().join(", "))
+            }
+            Text::BeatSolver => write!(f, "¡Has ganado al solucionador!"),
+            Text::Tie => write!(f, "Es un empate."),
+            Text::NeitherSolved => write!(f, "Ninguno de los dos lo ha resuelto."),
+            Text::SolverWins(guesses) => {
+                write!(f, "Gana el solucionador en {} intentos.", guesses)
+            }
+            Text::WorkingOutHandicap => write!(
+                f,
+                "Calculando la media del solucionador para fijar la ventaja."
+            ),
+            Text::NoHandicap(name) => write!(
+                f,
+                "{} no ha jugado suficientes partidas para calcular una ventaja.",
+                name
+            ),
+            Text::GuessHeadStart(player, solver) => write!(
+                f,
+                "Tu media es de {:.2} intentos y la del solucionador de {:.2}, así que empiezas con un intento de ventaja.",
+                player, solver
+            ),
+            Text::LetterHeadStart(player, solver, letter, position) => write!(
+                f,
+                "Tu media es de {:.2} intentos y la del solucionador de {:.2}, así que ahí va una letra: la respuesta tiene '{}' en la posición {}.",
+                player,
+                solver,
+                letter,
+                position + 1
+            ),
+            Text::SolverHeadStart(player, solver) => write!(
+                f,
+                "Tu media es de {:.2} intentos y la del solucionador de {:.2}, así que el solucionador empieza con un intento de ventaja.",
+                player, solver
+            ),
+            Text::CoachSummary(grades) => write!(
+                f,
+                "Entrenador: Tus intentos han sacado {}.",
+                grades.iter().join(", ")
+            ),
+            Text::CoachNarrowed(before, after) => write!(
+                f,
+                "Entrenador: {} respuestas posibles antes de ese intento, {} después.",
+                before, after
+            ),
+            Text::CoachGrade(grade, information, best) => write!(
+                f,
+                "Entrenador: Nota {}, con {:.2} bits de los {:.2} que podía dar el mejor intento.",
+                grade, information, best
+            ),
+            Text::CoachSameGuess => write!(
+                f,
+                "Entrenador: Es el intento que habría hecho el solucionador."
+            ),
+            Text::CoachCompare(yours, pick, solvers) => write!(
+                f,
+                "Entrenador: El tuyo deja {:.1} de media. El solucionador habría probado «{}», que deja {:.1}, así que el tuyo ha sido {}.",
+                yours,
+                pick,
+                solvers,
+                if yours < solvers {
+                    "mejor"
+                } else if yours > solvers {
+                    "peor"
+                } else {
+                    "igual de bueno"
+                }
+            ),
+            Text::HostRound(n, total) => write!(f, "Palabra {} de {}", n, total),
+            Text::ScoreSoFar(score) => write!(f, "Puntuación hasta ahora: {}", score),
+            Text::FinalScore(total, scores) => {
+                write!(f, "Puntuación final: {} ({})", total, scores_list(scores))
+            }
+            Text::EnterSecrets => write!(
+                f,
+                "Escribe las palabras secretas y luego una línea en blanco para empezar."
+            ),
+            Text::SecretPrompt(n) => write!(f, "Palabra secreta {}: ", n),
+            Text::SecretError(e) => {
+                write!(f, "La palabra {}, inténtalo de nuevo.", Text::WordError(e))
+            }
+            Text::EnterPlayers => write!(
+                f,
+                "Escribe el nombre de cada jugador en el orden en que vais a jugar y luego una línea en blanco para empezar."
+            ),
+            Text::PlayerPrompt(n) => write!(f, "Jugador {}: ", n),
+            Text::TooFewPlayers => write!(f, "Un relevo necesita al menos 2 jugadores."),
+            Text::SamePlayer(name) => write!(f, "{} ya está jugando.", name),
+            Text::RelayWinner(name) => write!(f, "¡{} ha acertado la palabra!", name),
+            Text::RevealIn(secs) => write!(f, "La respuesta se revela en {}s ", secs),
+            Text::NoHintStreaming => write!(f, "Las pistas están desactivadas en directo, porque mostrarían una letra de la respuesta."),
+        }
+    }
+}
+
+/// The words in quotes, with the last two joined by the word for "or"
+fn quoted(words: &[Word], or: &str) -> String {
+    let quoted: Vec<String> = words.iter().map(|w| format!("\"{}\"", w)).collect();
+    match quoted.split_last() {
+        Some((last, rest)) if !rest.is_empty() => {
+            format!("{} {} {}", rest.join(", "), or, last)
+        }
+        _ => quoted.concat(),
+    }
+}
+
+/// Each word's score, with X for a word that wasn't guessed
+fn scores_list(scores: &[usize]) -> String {
+    scores
+        .iter()
+        .map(|&s| match s {
+            s if s > crate::MAX_GUESSES => "X".to_string(),
+            s => s.to_string(),
+        })
+        .join(", ")
+}
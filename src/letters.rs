@@ -0,0 +1,57 @@
+use crate::dictionary::Alphabet;
+use crate::strategy::Frequencies;
+use owo_colors::{AnsiColors, OwoColorize};
+use std::fmt::Write;
+
+/// Print how often each symbol appears overall and in each position in some number
+/// of words, most common first
+pub fn display_letters(words: usize, freq: &Frequencies, alphabet: &Alphabet, heatmap: bool) {
+    let (total, positions) = (&freq.letters, &freq.positions);
+    let max = |freq: &[i32]| freq.iter().copied().max().unwrap_or(0);
+    let max_total = max(total);
+    let max_position = positions.iter().map(|freq| max(freq)).max().unwrap_or(0);
+
+    println!("{} words", words);
+    println!(
+        "{:>6}{:>7}  {:>6}{:>6}{:>6}{:>6}{:>6}",
+        "Letter", "Total", 1, 2, 3, 4, 5
+    );
+    let mut order: Vec<usize> = (0..alphabet.len()).collect();
+    order.sort_by_key(|&index| std::cmp::Reverse(total[index]));
+    for index in order {
+        let mut line = format!("{:>6}", alphabet.symbols()[index].to_ascii_uppercase());
+        write_cell(&mut line, total[index], max_total, heatmap, 7);
+        line.push_str("  ");
+        for freq in positions {
+            write_cell(&mut line, freq[index], max_position, heatmap, 6);
+        }
+        println!("{}", line);
+    }
+}
+
+fn write_cell(line: &mut String, count: i32, max: i32, heatmap: bool, width: usize) {
+    let cell = format!("{:>width$}", count, width = width);
+    if heatmap {
+        let fraction = if max > 0 {
+            count as f64 / max as f64
+        } else {
+            0.0
+        };
+        let cell = cell.color(AnsiColors::Black).on_color(heat_color(fraction));
+        write!(line, "{}", cell).unwrap();
+    } else {
+        line.push_str(&cell);
+    }
+}
+
+/// From cold to hot, for a count as a fraction of the largest
+fn heat_color(fraction: f64) -> AnsiColors {
+    const SCALE: [AnsiColors; 5] = [
+        AnsiColors::BrightBlack,
+        AnsiColors::Blue,
+        AnsiColors::Cyan,
+        AnsiColors::Yellow,
+        AnsiColors::Red,
+    ];
+    SCALE[((fraction * SCALE.len() as f64) as usize).min(SCALE.len() - 1)]
+}
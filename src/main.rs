@@ -1,42 +1,604 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
+use chrono_tz::Tz;
+use indicatif::{ProgressBar, ProgressStyle};
 use itertools::Itertools;
-use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::fmt::Write;
+use std::fs::File;
+use std::io::{BufWriter, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use structopt::StructOpt;
-use words::WORDS_USED;
+use unicode_normalization::UnicodeNormalization;
+
+use bench::FeedbackMatrix;
+use coach::Coach;
+use config::{Theme, TileColors};
+use dictionary::{Alphabet, Dictionary};
+use difficulty::Difficulty;
+use error::{WordError, WordleError};
+use events::Events;
+use export::Format;
+use game::{Game, GuessError};
+use handicap::Handicap;
+use i18n::{Lang, Text};
+use prompt::GuessHelper;
+use relay::Relay;
+use schedule::Schedule;
+use simulate::PlayerModel;
+use source::{Builtin, FileSource, WordSource};
+use stats::{DailyStats, GameRecord, Profile, Stats};
+use strategy::{Budgeted, Registry};
 
 const MAX_GUESSES: usize = 6;
 
+/// The most words suggested for a guess that isn't in the word list
+const TYPO_SUGGESTIONS: usize = 3;
+
+/// How many seconds `--streamer` counts down before revealing the answer, unless told
+const REVEAL_DELAY: u64 = 10;
+
+#[derive(StructOpt)]
+struct Args {
+    /// The language to show game messages in: "en" (English) or "es" (Spanish)
+    #[structopt(long, global = true, env = "WORDLE_UI_LANG", default_value = "en")]
+    ui_lang: Lang,
+    #[structopt(subcommand)]
+    command: Opt,
+}
+
 #[derive(StructOpt)]
 enum Opt {
     Solve {
         /// Enable words that are accepted, but can't be an answer. This more-accurately represents what the game allows.
         #[structopt(long)]
         more_words: bool,
+
+        /// The profile to record the session in.
+        #[structopt(long, env = "WORDLE_PROFILE", default_value = "default")]
+        profile: String,
+
+        /// If no word fits the results, look for a single tile that was entered wrong, and correct it.
+        #[structopt(long)]
+        fuzzy: bool,
+
+        /// Watch for feedback that always leaves the most candidates, as an adversarial host like Absurdle gives, and suggest for the worst case once it's spotted.
+        #[structopt(long)]
+        evil: bool,
+
+        /// When few candidates are left, weight their chances of being the answer by how common their letters are, instead of treating them as equally likely.
+        #[structopt(long)]
+        weighted: bool,
+
+        /// Keep the suggestions shown from being near-anagrams of each other, by passing over any that share 4 or more letters with a better one.
+        #[structopt(long)]
+        diverse: bool,
+
+        /// Write a JSON object per line to this file, or file descriptor number, as the game goes on.
+        #[structopt(long)]
+        events: Option<String>,
+
+        #[structopt(flatten)]
+        game: GameOpt,
+    },
+
+    Play {
+        /// Race the solver, which plays the same answer alongside you.
+        #[structopt(long)]
+        race: bool,
+
+        /// Help whichever of you and the solver averages more guesses, with a "guess" head start or a "letter" of the answer. The solver always gets a head start.
+        #[structopt(long, requires = "race")]
+        handicap: Option<Handicap>,
+
+        /// After each guess, show how much it narrowed down the answer compared to the solver's choice.
+        #[structopt(long)]
+        coach: bool,
+
+        /// Ask before locking in each guess, so a typo can be corrected instead of using up a guess.
+        #[structopt(long)]
+        confirm: bool,
+
+        /// Take turns guessing on the same board with other players, whose names are asked for first. The game is recorded in each player's profile.
+        #[structopt(long, conflicts_with_all = &["race", "daily"])]
+        relay: bool,
+
+        /// Hide the letters typed and show only the colors of each guess, so the game can be streamed without spoiling the answer. The answer is revealed after counting down this many seconds (10 by default).
+        #[structopt(long, conflicts_with_all = &["race", "coach", "confirm"])]
+        streamer: Option<Option<u64>>,
+
+        /// The strategy the solver races or coaches with. See `strategies` for the choices.
+        #[structopt(long, default_value = "frequency")]
+        strategy: String,
+
+        /// The most milliseconds the strategy can spend on a guess. Turns it couldn't finish in time use the frequency strategy instead.
+        #[structopt(long)]
+        time_budget: Option<u64>,
+
+        /// Play the word hidden in a code from `challenge create`, instead of a random answer.
+        #[structopt(long)]
+        challenge: Option<String>,
+
+        /// Play today's puzzle, which is the same for everyone and can only be played once.
+        #[structopt(long, conflicts_with = "challenge")]
+        daily: bool,
+
+        /// Play a past or future daily puzzle by its number. Unlike --daily, it can be replayed.
+        #[structopt(long, conflicts_with_all = &["challenge", "daily"])]
+        puzzle: Option<u64>,
+
+        /// Play an answer containing any of these letters, to practice them. Without letters, practice the ones in the answers the profile has found hardest.
+        #[structopt(long, conflicts_with_all = &["challenge", "daily", "puzzle"])]
+        practice_letters: Option<Option<String>>,
+
+        /// Play a random answer that's "easy", "medium" or "hard" to find, judging by how many guesses simulated players take.
+        #[structopt(long, conflicts_with_all = &["challenge", "daily", "puzzle", "practice-letters"])]
+        difficulty: Option<Difficulty>,
+
+        /// The time zone (e.g. "Europe/London") whose midnight starts the next daily puzzle. Defaults to the local one.
+        #[structopt(long, requires = "daily")]
+        tz: Option<Tz>,
+
+        /// Write a JSON object per line to this file, or file descriptor number, as the game goes on.
+        #[structopt(long)]
+        events: Option<String>,
+
+        /// The profile to record the game's result in.
+        #[structopt(long, env = "WORDLE_PROFILE", default_value = "default")]
+        profile: String,
+
+        #[structopt(flatten)]
+        game: GameOpt,
+    },
+
+    Stats {
+        /// The profile to show the stats of.
+        #[structopt(long, env = "WORDLE_PROFILE", default_value = "default")]
+        profile: String,
+    },
+
+    Profiles(ProfilesOpt),
+
+    Export {
+        /// "csv" or "json".
+        #[structopt(long, default_value = "csv")]
+        format: Format,
+
+        /// The file to write to. Defaults to standard output.
+        #[structopt(long, parse(from_os_str))]
+        output: Option<PathBuf>,
+
+        /// Only export this profile's history, instead of every profile's.
+        #[structopt(long)]
+        profile: Option<String>,
+    },
+
+    Leaderboard {
+        /// The time zone the daily puzzles were played in, for working out streaks. Defaults to the local one.
+        #[structopt(long)]
+        tz: Option<Tz>,
+    },
+
+    /// Say whether each profile has played today's daily puzzle, and how it did yesterday, for reminders from cron or a status bar. Exits with status 1 if a profile hasn't played today's puzzle yet.
+    DailySummary {
+        /// Only check this profile, instead of every profile.
+        #[structopt(long)]
+        profile: Option<String>,
+
+        /// The time zone whose midnight starts the next daily puzzle. Defaults to the local one.
+        #[structopt(long)]
+        tz: Option<Tz>,
+    },
+
+    Challenge(ChallengeOpt),
+
+    Cache(CacheOpt),
+
+    #[cfg(feature = "gui")]
+    Gui {
+        #[structopt(flatten)]
+        game: GameOpt,
+    },
+
+    Host {
+        #[structopt(flatten)]
+        game: GameOpt,
     },
 
-    Play,
+    Autoplay {
+        /// The answer for the solver to find. Defaults to a random one.
+        answer: Option<String>,
+
+        /// Find the answer to today's daily puzzle.
+        #[structopt(long, conflicts_with = "answer")]
+        today: bool,
+
+        /// Get today's answer from the official game, which needs an internet connection. It falls back to the offline answer if the official one can't be fetched.
+        #[structopt(long, requires = "today")]
+        online: bool,
+
+        /// The strategy to autoplay with. See `strategies` for the choices.
+        #[structopt(long, default_value = "frequency")]
+        strategy: String,
+
+        /// The most milliseconds the strategy can spend on a guess. Turns it couldn't finish in time use the frequency strategy instead.
+        #[structopt(long)]
+        time_budget: Option<u64>,
+
+        /// Always open with this guess, instead of the strategy's choice.
+        #[structopt(long)]
+        first_guess: Option<String>,
+
+        /// Show how the strategy chose each guess, for strategies that choose differently as the game goes on.
+        #[structopt(long)]
+        explain: bool,
+
+        /// Allow guessing words that are accepted, but can't be an answer.
+        #[structopt(long)]
+        more_words: bool,
+
+        #[structopt(flatten)]
+        game: GameOpt,
+    },
+
+    Bench {
+        /// The strategy to autoplay every answer with. See `strategies` for the choices. Giving a comma-separated list, or several first guesses, ranks every pairing of them.
+        #[structopt(long, default_value = "frequency", use_delimiter = true)]
+        strategy: Vec<String>,
+
+        /// Always open with this guess, instead of the strategy's choice. Takes a comma-separated list too.
+        #[structopt(long, use_delimiter = true)]
+        first_guess: Vec<String>,
+
+        /// Allow guessing words that are accepted, but can't be an answer.
+        #[structopt(long)]
+        more_words: bool,
+
+        /// Write the result of every game to a CSV file.
+        #[structopt(long, parse(from_os_str))]
+        output: Option<PathBuf>,
+
+        #[structopt(flatten)]
+        game: GameOpt,
+    },
+
+    Compare {
+        /// Comma-separated list of the strategies to autoplay every answer with. Defaults to all of them.
+        #[structopt(long, use_delimiter = true)]
+        strategies: Vec<String>,
+
+        /// Only autoplay this many answers picked at random, for speed. The means are shown with 95% confidence intervals, and strategies that can't be told apart from the best are flagged.
+        #[structopt(long)]
+        sample: Option<usize>,
+
+        /// Allow guessing words that are accepted, but can't be an answer.
+        #[structopt(long)]
+        more_words: bool,
+
+        /// Write the result of every game to a CSV file.
+        #[structopt(long, parse(from_os_str))]
+        output: Option<PathBuf>,
+
+        #[structopt(flatten)]
+        game: GameOpt,
+    },
+
+    Check {
+        /// The word to look up.
+        word: String,
+
+        #[structopt(flatten)]
+        game: GameOpt,
+    },
+
+    DiffLists {
+        /// The old list of answers.
+        #[structopt(parse(from_os_str))]
+        old: PathBuf,
+
+        /// The new list of answers.
+        #[structopt(parse(from_os_str))]
+        new: PathBuf,
+
+        /// The old list of words that are accepted, but can't be an answer.
+        #[structopt(long, parse(from_os_str), requires = "new-extra")]
+        old_extra: Option<PathBuf>,
+
+        /// The new list of words that are accepted, but can't be an answer.
+        #[structopt(long, parse(from_os_str), requires = "old-extra")]
+        new_extra: Option<PathBuf>,
+
+        /// The symbols the lists are spelled with, as single symbols and ranges (e.g. "a-z'").
+        #[structopt(long, default_value = "a-z")]
+        alphabet: Alphabet,
+    },
+
+    Reverse {
+        /// The answer of the finished game.
+        answer: String,
+
+        /// The rows of the grid, as colored squares or as g, y and b (or .) for each tile.
+        #[structopt(required = true)]
+        rows: Vec<String>,
+
+        /// How many example sequences of guesses to show.
+        #[structopt(long, default_value = "5")]
+        sequences: usize,
+
+        /// Allow guesses that are accepted, but can't be an answer.
+        #[structopt(long)]
+        more_words: bool,
+
+        #[structopt(flatten)]
+        game: GameOpt,
+    },
+
+    /// Check that the feedback in a game really is what the answer gives, e.g. to settle whether a game was scored right.
+    Verify {
+        /// The answer the game was supposedly played with.
+        answer: String,
+
+        /// Each guess and its feedback, e.g. "crate:gggb.", with the feedback as colored squares or as g, y and b (or .) for each tile.
+        #[structopt(required = true)]
+        turns: Vec<String>,
+
+        #[structopt(flatten)]
+        game: GameOpt,
+    },
+
+    /// Filter, sample, score and partition the word lists from a prompt, for studying the game rather than playing it.
+    Explore {
+        /// Start from every accepted word, not just the answers.
+        #[structopt(long)]
+        more_words: bool,
+
+        #[structopt(flatten)]
+        game: GameOpt,
+    },
+
+    Find {
+        /// Letters the words have to have. Listing one more than once requires that many.
+        #[structopt(long)]
+        contains: Option<String>,
+
+        /// Letters the words can't have.
+        #[structopt(long)]
+        exclude: Option<String>,
+
+        /// The symbol in each position, with ? (or .) for any, e.g. "?a??e".
+        #[structopt(long)]
+        pattern: Option<String>,
+
+        /// Include words that are accepted, but can't be an answer.
+        #[structopt(long)]
+        more_words: bool,
+
+        #[structopt(flatten)]
+        game: GameOpt,
+    },
+
+    Analyze {
+        /// Analyze a daily puzzle someone shared, from the text shared after playing. Without the text, it's read from stdin, so it can be pasted.
+        #[structopt(long)]
+        share: Option<Option<String>>,
+
+        /// Report on many games at once, such as a group chat's, from a file with a line per game: optionally a player's name and a colon, then the puzzle number and the grid, e.g. "alice: 1234 ⬛⬛🟨⬛⬛ 🟩🟩🟩🟩🟩".
+        #[structopt(long, parse(from_os_str), conflicts_with_all = &["share", "online"])]
+        batch: Option<PathBuf>,
+
+        /// The strategy whose number of guesses the game is compared to. See `strategies` for the choices.
+        #[structopt(long, default_value = "frequency")]
+        strategy: String,
+
+        /// How many example sequences of guesses to show.
+        #[structopt(long, default_value = "5")]
+        sequences: usize,
+
+        /// Get the puzzle's answer from the official game, which needs an internet connection. It falls back to the offline answer if the official one can't be fetched.
+        #[structopt(long)]
+        online: bool,
+
+        #[structopt(flatten)]
+        game: GameOpt,
+    },
+
+    /// Autoplay many games and write each turn as a line of JSON, for training and evaluating other guessing models.
+    GenerateDataset {
+        /// How many games to play, each of an answer picked at random.
+        #[structopt(long, default_value = "1000")]
+        games: usize,
+
+        /// The file to write to. Each line has the game and turn numbers, the answer, the candidates left before the turn, the guess, and the feedback as each tile's code (e.g. "20100", where 2 is correct and, for Wordle, 1 is present).
+        #[structopt(long, parse(from_os_str))]
+        output: PathBuf,
+
+        /// The strategy to play with. See `strategies` for the choices.
+        #[structopt(long, default_value = "frequency")]
+        strategy: String,
+
+        /// The fraction of guesses that are a random candidate instead of the strategy's choice, to play more like a person.
+        #[structopt(long, default_value = "0")]
+        noise: f64,
+
+        #[structopt(flatten)]
+        game: GameOpt,
+    },
+
+    Simulate {
+        /// Write each answer's mean number of guesses for each player model to a CSV file.
+        #[structopt(long, parse(from_os_str))]
+        output: Option<PathBuf>,
+
+        #[structopt(flatten)]
+        game: GameOpt,
+    },
+
+    Strategies,
+
+    Letters {
+        /// Color each count by how common it is.
+        #[structopt(long)]
+        heatmap: bool,
+
+        /// Include words that are accepted, but can't be an answer.
+        #[structopt(long)]
+        more_words: bool,
+
+        #[structopt(flatten)]
+        game: GameOpt,
+    },
 }
 
-mod words {
-    include!(concat!(env!("OUT_DIR"), "/words.rs"));
+#[derive(StructOpt)]
+enum ChallengeOpt {
+    /// Make a code for a word, which someone else can play with `play --challenge`.
+    Create {
+        /// The word to challenge them with, which is asked for without showing it if left out. It doesn't have to be in the word list.
+        word: Option<String>,
+
+        #[structopt(flatten)]
+        game: GameOpt,
+    },
+}
+
+#[derive(StructOpt)]
+enum CacheOpt {
+    /// Delete the cached feedback for every guess and answer, which is worked out again when needed.
+    Clear,
+}
+
+#[derive(StructOpt)]
+enum ProfilesOpt {
+    /// List the profiles that have played a game.
+    List,
+
+    /// Delete a profile's stats and history.
+    Delete {
+        /// The profile to delete.
+        name: String,
+    },
 }
 
-#[derive(Debug)]
-struct Hint<'a> {
-    omit_letters: &'a [char],
-    req_letters: &'a [char],
-    cand_letters: Option<&'a [FoundLetter]>,
+#[derive(StructOpt)]
+struct GameOpt {
+    /// Use the Wordle Peaks variant, where each tile tells whether the answer's letter comes earlier or later in the alphabet.
+    #[structopt(long)]
+    peaks: bool,
+
+    /// Use the Primel variant, where the answer is a five-digit prime number.
+    #[structopt(long, conflicts_with = "wordlist")]
+    primel: bool,
+
+    /// Use a custom list of answers, one per line, instead of the built-in list.
+    #[structopt(long, parse(from_os_str))]
+    wordlist: Option<PathBuf>,
+
+    /// The symbols the custom list is spelled with, as single symbols and ranges (e.g. "a-z'").
+    #[structopt(long, requires = "wordlist")]
+    alphabet: Option<Alphabet>,
+
+    /// Work out the feedback for every guess and answer afresh, instead of using or filling the cache.
+    #[structopt(long)]
+    no_cache: bool,
+
+    /// The official daily answers, one per line, optionally after their date (e.g. "2021-06-19 cigar") or puzzle number. Defaults to schedule.txt in the config directory, if it's there.
+    #[structopt(long, parse(from_os_str))]
+    schedule: Option<PathBuf>,
+}
+
+impl GameOpt {
+    fn word_source(&self) -> Box<dyn WordSource> {
+        match &self.wordlist {
+            Some(path) => Box::new(FileSource::new(
+                path,
+                self.alphabet.clone().unwrap_or_else(Alphabet::letters),
+            )),
+            None if self.primel => Box::new(Builtin::primel()),
+            None => Box::new(Builtin::english()),
+        }
+    }
+
+    fn dictionary(&self) -> Result<Dictionary> {
+        let mut dict = Dictionary::load(self.word_source().as_ref())?;
+        dict.schedule = Schedule::load(self.schedule.as_deref(), &dict.alphabet)?;
+        Ok(dict)
+    }
+
+    /// Build the feedback matrix for autoplaying, making sure it includes the first guesses
+    fn feedback_matrix(
+        &self,
+        dict: &Dictionary,
+        more_words: bool,
+        first_guesses: &[Word],
+    ) -> FeedbackMatrix {
+        let mut extra = if more_words {
+            dict.extra.clone()
+        } else {
+            vec![]
+        };
+        for &word in first_guesses {
+            if !dict.answers.contains(&word) && !extra.contains(&word) {
+                extra.push(word);
+            }
+        }
+        let matrix = match (self.peaks, self.no_cache) {
+            (true, true) => FeedbackMatrix::new(&dict.answers, &extra, peaks_word),
+            (true, false) => FeedbackMatrix::cached(&dict.answers, &extra, peaks_word, "peaks"),
+            (false, true) => FeedbackMatrix::new(&dict.answers, &extra, guess_word),
+            (false, false) => FeedbackMatrix::cached(&dict.answers, &extra, guess_word, "wordle"),
+        };
+        matrix.with_frequencies(dict.frequencies(false))
+    }
+
+    /// Parse a guess given on the command line, which has to be in the word list
+    fn parse_guess(&self, dict: &Dictionary, guess: &str) -> Result<Word, WordleError> {
+        let word = dict.alphabet.parse_word(guess)?;
+        if !dict.is_accepted(&word) {
+            return Err(WordleError::NotInDictionary(guess.to_string()));
+        }
+        Ok(word)
+    }
 }
 
-#[derive(Debug)]
-struct FoundLetter {
-    letter: char,
-    position: usize,
-    correct_location: bool,
+mod batch;
+mod bench;
+mod cache;
+mod challenge;
+mod coach;
+mod config;
+mod dataset;
+mod dictionary;
+mod difficulty;
+mod error;
+mod events;
+mod explore;
+mod export;
+mod find;
+mod game;
+#[cfg(feature = "gui")]
+mod gui;
+mod handicap;
+mod i18n;
+mod letters;
+mod online;
+mod prompt;
+mod relay;
+mod reverse;
+mod schedule;
+#[cfg(feature = "script")]
+mod script;
+mod share;
+mod simulate;
+mod solve;
+mod source;
+mod stats;
+mod strategy;
+
+mod words {
+    include!(concat!(env!("OUT_DIR"), "/words.rs"));
 }
 
-#[derive(Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct Word([char; 5]);
 
 impl std::fmt::Display for Word {
@@ -58,15 +620,956 @@ impl std::fmt::Debug for Word {
 }
 
 fn main() -> Result<()> {
-    let opt = Opt::from_args();
+    let Args {
+        ui_lang,
+        command: opt,
+    } = Args::from_args();
+    ui_lang.set()?;
+    Theme::load()?;
 
     match opt {
-        Opt::Solve { more_words } => solve(more_words)?,
-        Opt::Play => {
-            let answer = random_answer();
-            match play(answer) {
-                Ok(guesses) => println!("Good job! It took you {} guesses", guesses),
-                Err(_) => println!("Better luck next time.  The answer was \"{}\".", answer),
+        Opt::Solve {
+            more_words,
+            profile,
+            fuzzy,
+            evil,
+            weighted,
+            diverse,
+            events,
+            game,
+        } => {
+            let profile = Profile::open(&profile)?;
+            let dict = game.dictionary()?;
+            let words = dict.words(more_words);
+            let freq = dict.frequencies(more_words);
+            let mut events = open_events(events)?;
+            let session = if game.peaks {
+                solve::solve_peaks(words, &freq.letters, &dict.alphabet, weighted, &mut events)?
+            } else {
+                let accepted = dict.words(true);
+                let options = solve::Options {
+                    weighted,
+                    fuzzy,
+                    evil,
+                    diverse,
+                };
+                solve::solve(
+                    words,
+                    &freq.letters,
+                    &accepted,
+                    &dict.alphabet,
+                    options,
+                    &mut events,
+                )?
+            };
+            profile.record_session(&session)?;
+        }
+        Opt::Play {
+            race,
+            handicap,
+            coach,
+            confirm,
+            relay,
+            streamer,
+            strategy,
+            time_budget,
+            challenge,
+            daily,
+            puzzle: past_puzzle,
+            practice_letters,
+            difficulty,
+            tz,
+            events,
+            profile,
+            game,
+        } => {
+            let profile = Profile::open(&profile)?;
+            let mut events = open_events(events)?;
+            let mut dict = game.dictionary()?;
+            // The puzzle is fixed when the game starts, even if it's finished after midnight
+            let puzzle = stats::todays_puzzle(tz);
+            let mode = if daily {
+                GameRecord::daily(puzzle)
+            } else if relay {
+                "relay".to_string()
+            } else if challenge.is_some() {
+                "challenge".to_string()
+            } else if let Some(past_puzzle) = past_puzzle {
+                format!("puzzle-{}", past_puzzle)
+            } else if practice_letters.is_some() {
+                "practice".to_string()
+            } else if let Some(difficulty) = difficulty {
+                format!("random-{:?}", difficulty).to_lowercase()
+            } else {
+                "random".to_string()
+            };
+            if daily && profile.history()?.iter().any(|g| g.mode == mode) {
+                return Err(anyhow!(
+                    "{} has already played today's puzzle (#{})",
+                    profile.name,
+                    puzzle
+                ));
+            }
+            let registry = strategy_registry(std::slice::from_ref(&strategy))?;
+            let budgeted;
+            let strategy: &dyn strategy::Strategy = match time_budget {
+                Some(ms) => {
+                    budgeted = Budgeted::new(registry.get(&strategy)?, Duration::from_millis(ms));
+                    &budgeted
+                }
+                None => registry.get(&strategy)?,
+            };
+            let answer = match challenge {
+                Some(code) => {
+                    let word = challenge::decode(&code)?;
+                    let word = dict.alphabet.parse_word(&word.to_string())?;
+                    // A custom word has to be guessable, and findable by the solver
+                    dict.add_answer(word);
+                    word
+                }
+                None if daily || past_puzzle.is_some() => {
+                    let word = dict.daily_answer(past_puzzle.unwrap_or(puzzle));
+                    dict.add_answer(word);
+                    word
+                }
+                None => match practice_letters {
+                    Some(letters) => {
+                        let letters = match letters {
+                            Some(letters) => parse_letters(&dict.alphabet, &letters)?,
+                            None => {
+                                let letters = stats::weak_letters(&profile.history()?, 3);
+                                if letters.is_empty() {
+                                    return Err(anyhow!(
+                                        "{} hasn't played enough to know which letters to practice",
+                                        profile.name
+                                    ));
+                                }
+                                println!("Practicing {}.", letters.iter().join(", "));
+                                letters
+                            }
+                        };
+                        let has_letter = |word: &Word| word.0.iter().any(|c| letters.contains(c));
+                        dict.random_answer_where(has_letter).ok_or_else(|| {
+                            anyhow!("No answer contains {}", letters.iter().join(", "))
+                        })?
+                    }
+                    None => match difficulty {
+                        Some(difficulty) => {
+                            let matrix = game.feedback_matrix(&dict, false, &[]);
+                            let estimates = simulate::human_difficulty(&matrix, &dict.alphabet);
+                            let tier = difficulty::tier(
+                                matrix.answers(),
+                                &estimates,
+                                &dict.alphabet,
+                                difficulty,
+                            );
+                            dict.random_answer_where(|word| tier.contains(word))
+                                .ok_or_else(|| anyhow!("There are too few answers to have tiers"))?
+                        }
+                        None => dict.random_answer(),
+                    },
+                },
+            };
+            let rival = if race {
+                let matrix = game.feedback_matrix(&dict, false, &[]);
+                bench::autoplay_answer(&matrix, &dict.alphabet, strategy, answer, None)
+            } else {
+                vec![]
+            };
+            // A head start takes a guess off the result of whoever gets it
+            let (mut player_head_start, mut solver_head_start) = (0, 0);
+            if let Some(handicap) = handicap {
+                let matrix = game.feedback_matrix(&dict, false, &[]);
+                // Scripts can change under the same name, and a time budget makes the
+                // solver's guesses depend on how fast the machine is
+                let cache = !game.no_cache
+                    && time_budget.is_none()
+                    && !strategy.name().starts_with("script:");
+                let solver = match bench::cached_mean(&matrix, strategy).filter(|_| cache) {
+                    Some(mean) => mean,
+                    None => {
+                        println!("{}", Text::WorkingOutHandicap);
+                        let progress = progress_bar(&matrix, strategy.name());
+                        let mean = bench::bench(&matrix, &dict.alphabet, strategy, None, &progress)
+                            .mean_guesses();
+                        progress.finish_and_clear();
+                        if cache {
+                            if let Err(e) = bench::cache_mean(&matrix, strategy, mean) {
+                                eprintln!("Couldn't cache the solver's average: {:#}", e);
+                            }
+                        }
+                        mean
+                    }
+                };
+                match handicap::mean_guesses(&profile.history()?) {
+                    None => println!("{}", Text::NoHandicap(&profile.name)),
+                    Some(player) if player > solver => match handicap {
+                        Handicap::Guess => {
+                            println!("{}", Text::GuessHeadStart(player, solver));
+                            player_head_start = 1;
+                        }
+                        Handicap::Letter => {
+                            let position = rand::random::<usize>() % answer.0.len();
+                            println!(
+                                "{}",
+                                Text::LetterHeadStart(player, solver, answer.0[position], position)
+                            );
+                        }
+                    },
+                    Some(player) => {
+                        println!("{}", Text::SolverHeadStart(player, solver));
+                        solver_head_start = 1;
+                    }
+                }
+            }
+            let coach = coach.then(|| {
+                // The player can guess any accepted word
+                let matrix = game.feedback_matrix(&dict, true, &[]);
+                Coach::new(matrix, &dict.alphabet, strategy)
+            });
+            let mut relay = match relay {
+                true => Some(Relay::ask_players()?),
+                false => None,
+            };
+            let start = Instant::now();
+            let options = PlayOptions {
+                rival: &rival,
+                coach,
+                relay: relay.as_mut(),
+                confirm,
+                streamer: streamer.map(|delay| delay.unwrap_or(REVEAL_DELAY)),
+            };
+            let result = if game.peaks {
+                play(answer, &dict, peaks_word, options, &mut events)
+            } else {
+                play(answer, &dict, guess_word, options, &mut events)
+            };
+            match &result {
+                Ok(guesses) => println!("{}", Text::GoodJob(*guesses)),
+                Err(_) => println!("{}", Text::BetterLuck(answer)),
+            }
+            if let Some(relay) = &relay {
+                print!("\n{}", relay.grid());
+                if let Some(winner) = relay.winner() {
+                    println!("{}", Text::RelayWinner(winner));
+                }
+            }
+            // Quitting partway through doesn't count as a game
+            if !matches!(
+                result,
+                Err(WordleError::Interrupted | WordleError::Readline(_))
+            ) {
+                let guesses = result.as_ref().ok().copied();
+                events.game_over(Some(answer), guesses)?;
+                match &relay {
+                    Some(relay) => relay.record(answer, guesses, start.elapsed())?,
+                    None => {
+                        profile.record(&GameRecord::new(&mode, answer, guesses, start.elapsed()))?
+                    }
+                }
+            }
+            if daily {
+                let wait = stats::until_next_puzzle(tz).as_secs();
+                println!("{}", Text::NextPuzzle(wait));
+            }
+            // Quitting partway through leaves the race without a winner
+            if race
+                && !matches!(
+                    result,
+                    Err(WordleError::Interrupted | WordleError::Readline(_))
+                )
+            {
+                println!("{}", Text::SolverGuessed(&rival));
+                let solver_solved = rival.len() <= MAX_GUESSES;
+                let solver = rival.len() - solver_head_start;
+                match result {
+                    Ok(_) if !solver_solved => println!("{}", Text::BeatSolver),
+                    Ok(guesses) if guesses - player_head_start < solver => {
+                        println!("{}", Text::BeatSolver)
+                    }
+                    Ok(guesses) if guesses - player_head_start == solver => {
+                        println!("{}", Text::Tie)
+                    }
+                    _ if !solver_solved => println!("{}", Text::NeitherSolved),
+                    _ => println!("{}", Text::SolverWins(solver)),
+                }
+            }
+        }
+        Opt::Autoplay {
+            answer,
+            today,
+            online,
+            strategy,
+            time_budget,
+            first_guess,
+            explain,
+            more_words,
+            game,
+        } => {
+            let registry = strategy_registry(std::slice::from_ref(&strategy))?;
+            let budgeted;
+            let strategy: &dyn strategy::Strategy = match time_budget {
+                Some(ms) => {
+                    budgeted = Budgeted::new(registry.get(&strategy)?, Duration::from_millis(ms));
+                    &budgeted
+                }
+                None => registry.get(&strategy)?,
+            };
+            let mut dict = game.dictionary()?;
+            let answer = match answer {
+                None if today => daily_answer(&mut dict, stats::todays_puzzle(None), online),
+                Some(answer) => {
+                    let word = dict.alphabet.parse_word(&answer)?;
+                    if !dict.answers.contains(&word) {
+                        return Err(anyhow!("\"{}\" can't be the answer", answer));
+                    }
+                    word
+                }
+                None => dict.random_answer(),
+            };
+            let first_guess = first_guess
+                .map(|guess| game.parse_guess(&dict, &guess))
+                .transpose()?;
+            let matrix = game.feedback_matrix(&dict, more_words, first_guess.as_slice());
+            let guesses =
+                bench::autoplay_answer(&matrix, &dict.alphabet, strategy, answer, first_guess);
+            let mut candidates: Vec<usize> = (0..matrix.answers().len()).collect();
+            let answer_index = matrix.answers().iter().position(|&a| a == answer);
+            for (i, &guess) in guesses.iter().enumerate() {
+                if game.peaks {
+                    print!("{}. {}", i, peaks_word(guess, answer));
+                } else {
+                    print!("{}. {}", i, guess_word(guess, answer));
+                }
+                // A pinned opening and the last two candidates aren't the strategy's choice
+                let chosen = !(i == 0 && first_guess.is_some()) && candidates.len() > 2;
+                match strategy.explain(&matrix, &candidates) {
+                    Some(reason) if explain && chosen => println!("  ({})", reason),
+                    _ => println!(),
+                }
+                if let (Some(guess), Some(answer)) = (matrix.guess_index(guess), answer_index) {
+                    let pattern = matrix.pattern(guess, answer);
+                    candidates.retain(|&c| matrix.pattern(guess, c) == pattern);
+                }
+            }
+            if guesses.len() <= MAX_GUESSES {
+                println!("Solved \"{}\" in {} guesses", answer, guesses.len());
+            } else {
+                println!("Failed to solve \"{}\"", answer);
+            }
+        }
+        Opt::Bench {
+            strategy,
+            first_guess,
+            more_words,
+            output,
+            game,
+        } => {
+            let registry = strategy_registry(&strategy)?;
+            let strategies = strategy
+                .iter()
+                .map(|name| registry.get(name))
+                .collect::<Result<Vec<_>>>()?;
+            let dict = game.dictionary()?;
+            let first_guesses = first_guess
+                .iter()
+                .map(|guess| game.parse_guess(&dict, guess))
+                .collect::<Result<Vec<_>, _>>()?;
+            let matrix = game.feedback_matrix(&dict, more_words, &first_guesses);
+            if strategies.len() > 1 || first_guesses.len() > 1 {
+                return sweep(&matrix, &dict.alphabet, &strategies, &first_guesses, output);
+            }
+            let strategy = strategies[0];
+            let first_guess = first_guesses.first().copied();
+            let progress = progress_bar(&matrix, strategy.name());
+            let result = bench::bench(&matrix, &dict.alphabet, strategy, first_guess, &progress);
+            progress.finish_and_clear();
+            println!(
+                "{}: {:.3} guesses on average, {} of {} failed, in {:.2?}",
+                strategy.name(),
+                result.mean_guesses(),
+                result.failures(),
+                result.games.len(),
+                result.elapsed
+            );
+            for (i, count) in result.distribution().iter().enumerate() {
+                println!("{:2}: {:5}", i + 1, count);
+            }
+            let worst_cases = result.worst_cases();
+            if !worst_cases.is_empty() {
+                println!("Answers that took {} or more guesses:", MAX_GUESSES);
+                for game in worst_cases {
+                    println!(
+                        "{} {:>2}{}: {}",
+                        game.answer,
+                        game.guesses.len(),
+                        if game.solved() { "" } else { " (failed)" },
+                        game.guesses.iter().join(", ")
+                    );
+                }
+            }
+            if let Some(path) = output {
+                write_csv(&path, &[(strategy.name(), &result)])?;
+            }
+        }
+        Opt::Compare {
+            strategies,
+            sample,
+            more_words,
+            output,
+            game,
+        } => {
+            let registry = strategy_registry(&strategies)?;
+            let strategies = if strategies.is_empty() {
+                registry.iter().collect()
+            } else {
+                strategies
+                    .iter()
+                    .map(|name| registry.get(name))
+                    .collect::<Result<Vec<_>>>()?
+            };
+            if sample == Some(0) {
+                return Err(anyhow!("The sample needs at least one answer"));
+            }
+            let dict = game.dictionary()?;
+            let matrix = game.feedback_matrix(&dict, more_words, &[]);
+            let answers = match sample {
+                Some(count) => bench::sample_answers(&matrix, count),
+                None => (0..matrix.answers().len()).collect(),
+            };
+            let width = strategies.iter().map(|s| s.name().len()).max().unwrap_or(0);
+            if sample.is_some() {
+                println!(
+                    "Autoplaying {} of the {} answers.",
+                    answers.len(),
+                    matrix.answers().len()
+                );
+            }
+            println!(
+                "{:<width$} {:>6} {:>13} {:>6} {:>5} {:>10}",
+                "Strategy",
+                "Mean",
+                if sample.is_some() { "95% CI" } else { "" },
+                "Failed",
+                "Worst",
+                "Time",
+                width = width.max(8)
+            );
+            let mut results = vec![];
+            for strategy in strategies {
+                let progress = progress_bar(&matrix, strategy.name());
+                progress.set_length(answers.len() as u64);
+                let result = bench::bench_answers(
+                    &matrix,
+                    &dict.alphabet,
+                    strategy,
+                    None,
+                    &answers,
+                    &progress,
+                );
+                progress.finish_and_clear();
+                let interval = match sample {
+                    Some(_) => {
+                        let (low, high) = result.confidence_interval();
+                        format!("{:.3}-{:.3}", low, high)
+                    }
+                    None => String::new(),
+                };
+                println!(
+                    "{:<width$} {:>6.3} {:>13} {:>6} {:>5} {:>10.2?}",
+                    strategy.name(),
+                    result.mean_guesses(),
+                    interval,
+                    result.failures(),
+                    result.worst(),
+                    result.elapsed,
+                    width = width.max(8)
+                );
+                results.push((strategy.name(), result));
+            }
+            if sample.is_some() {
+                display_indistinguishable(&results);
+            }
+            if let Some(path) = output {
+                let results: Vec<(&str, &bench::Bench)> = results
+                    .iter()
+                    .map(|(name, result)| (*name, result))
+                    .collect();
+                write_csv(&path, &results)?;
+            }
+        }
+        Opt::Reverse {
+            answer,
+            rows,
+            sequences,
+            more_words,
+            game,
+        } => {
+            if game.peaks {
+                return Err(anyhow!("Reverse only works out Wordle grids"));
+            }
+            let dict = game.dictionary()?;
+            let word = dict.alphabet.parse_word(&answer)?;
+            let matrix = game.feedback_matrix(&dict, more_words, &[]);
+            let answer = match matrix.answers().iter().position(|&w| w == word) {
+                Some(answer) => answer,
+                None => return Err(anyhow!("\"{}\" can't be the answer", answer)),
+            };
+            let rows = rows
+                .iter()
+                .map(|row| reverse::parse_row(row))
+                .collect::<Result<Vec<_>>>()?;
+            reverse::reverse(&matrix, answer, &rows, sequences)?;
+        }
+        Opt::Verify {
+            answer,
+            turns,
+            game,
+        } => {
+            if game.peaks {
+                return Err(anyhow!("Verify only checks Wordle feedback"));
+            }
+            let dict = game.dictionary()?;
+            let answer = dict.alphabet.parse_word(&answer)?;
+            let turns = turns
+                .iter()
+                .map(|turn| {
+                    let (guess, row) = turn.split_once(':').ok_or_else(|| {
+                        anyhow!(
+                            "\"{}\" should be a guess and feedback, e.g. \"crate:gggb.\"",
+                            turn
+                        )
+                    })?;
+                    Ok((dict.alphabet.parse_word(guess)?, reverse::parse_row(row)?))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let wrong = reverse::mismatches(answer, &turns);
+            for (i, &(guess, given)) in turns.iter().enumerate() {
+                match wrong.iter().find(|&&(turn, _)| turn == i) {
+                    Some(&(_, actual)) => println!(
+                        "{}. {} {}  wrong, should be {}",
+                        i + 1,
+                        guess,
+                        reverse::format_row(given),
+                        reverse::format_row(actual)
+                    ),
+                    None => println!("{}. {} {}  ok", i + 1, guess, reverse::format_row(given)),
+                }
+            }
+            if !wrong.is_empty() {
+                return Err(anyhow!(
+                    "{} of the {} turns weren't scored as \"{}\" would score them",
+                    wrong.len(),
+                    turns.len(),
+                    answer
+                ));
+            }
+            println!("Every turn was scored right for \"{}\".", answer);
+        }
+        Opt::Explore { more_words, game } => {
+            if game.peaks {
+                return Err(anyhow!("Explore only works with Wordle feedback"));
+            }
+            explore::explore(&game.dictionary()?, more_words)?;
+        }
+        Opt::Find {
+            contains,
+            exclude,
+            pattern,
+            more_words,
+            game,
+        } => {
+            let dict = game.dictionary()?;
+            let query = find::Query::new(
+                &dict.alphabet,
+                contains.as_deref(),
+                exclude.as_deref(),
+                pattern.as_deref(),
+            )?;
+            let words: Vec<Word> = dict
+                .words(more_words)
+                .into_iter()
+                .filter(|word| query.matches(word))
+                .collect();
+            for word in &words {
+                println!("{}", word);
+            }
+            eprintln!("{} words", words.len());
+        }
+        Opt::Analyze {
+            share,
+            batch,
+            strategy,
+            sequences,
+            online,
+            game,
+        } => {
+            if game.peaks {
+                return Err(anyhow!("Analyze only works out Wordle grids"));
+            }
+            if let Some(path) = batch {
+                let text = std::fs::read_to_string(&path)
+                    .with_context(|| format!("reading {}", path.display()))?;
+                let entries =
+                    batch::parse(&text).with_context(|| format!("reading {}", path.display()))?;
+                if entries.is_empty() {
+                    return Err(anyhow!("{} has no games in it", path.display()));
+                }
+                batch::report(&entries, &game.dictionary()?);
+                return Ok(());
+            }
+            let text = match share {
+                Some(Some(text)) => text,
+                Some(None) => {
+                    println!("Paste the shared game, then press Ctrl-D.");
+                    std::io::read_to_string(std::io::stdin())?
+                }
+                None => {
+                    return Err(anyhow!(
+                        "Nothing to analyze. Use --share for a shared game, or --batch for many."
+                    ))
+                }
+            };
+            let share = share::Share::parse(&text)?;
+            let registry = strategy_registry(std::slice::from_ref(&strategy))?;
+            let strategy = registry.get(&strategy)?;
+            let mut dict = game.dictionary()?;
+            let word = daily_answer(&mut dict, share.puzzle, online);
+            // Anyone sharing a game could have guessed any accepted word
+            let matrix = game.feedback_matrix(&dict, true, &[]);
+            let answer = dict.answers.iter().position(|&w| w == word).unwrap();
+            println!("Puzzle #{}, whose answer is \"{}\"", share.puzzle, word);
+            reverse::reverse(&matrix, answer, &share.rows, sequences)?;
+
+            let par = bench::autoplay_answer(&matrix, &dict.alphabet, strategy, word, None).len();
+            match share.guesses {
+                Some(guesses) if guesses < par => println!(
+                    "Solved in {}, beating the {} strategy's {}.",
+                    guesses,
+                    strategy.name(),
+                    par
+                ),
+                Some(guesses) if guesses == par => println!(
+                    "Solved in {}, the same as the {} strategy.",
+                    guesses,
+                    strategy.name()
+                ),
+                Some(guesses) => println!(
+                    "Solved in {}, where the {} strategy takes {}.",
+                    guesses,
+                    strategy.name(),
+                    par
+                ),
+                None => println!(
+                    "Not solved, where the {} strategy takes {}.",
+                    strategy.name(),
+                    par
+                ),
+            }
+        }
+        Opt::GenerateDataset {
+            games,
+            output,
+            strategy,
+            noise,
+            game,
+        } => {
+            if !(0.0..=1.0).contains(&noise) {
+                return Err(anyhow!("The noise has to be between 0 and 1"));
+            }
+            let registry = strategy_registry(std::slice::from_ref(&strategy))?;
+            let strategy = registry.get(&strategy)?;
+            let dict = game.dictionary()?;
+            let matrix = game.feedback_matrix(&dict, false, &[]);
+            let file =
+                File::create(&output).with_context(|| format!("creating {}", output.display()))?;
+            let progress = progress_bar(&matrix, strategy.name());
+            progress.set_length(games as u64);
+            dataset::generate(
+                &matrix,
+                &dict.alphabet,
+                strategy,
+                games,
+                noise,
+                BufWriter::new(file),
+                &progress,
+            )?;
+            progress.finish_and_clear();
+            println!("Wrote {} games to {}", games, output.display());
+        }
+        Opt::Simulate { output, game } => {
+            let dict = game.dictionary()?;
+            let matrix = game.feedback_matrix(&dict, false, &[]);
+            let mut results = vec![];
+            for model in PlayerModel::ALL {
+                let progress = progress_bar(&matrix, model.name());
+                let guesses = model.simulate(&matrix, &dict.alphabet, &progress);
+                progress.finish_and_clear();
+                let solved = guesses.iter().filter(|&&g| g <= MAX_GUESSES as f64).count();
+                println!(
+                    "{:<10} {:.3} guesses on average, {:.1}% solved",
+                    model.name(),
+                    guesses.iter().sum::<f64>() / guesses.len() as f64,
+                    100.0 * solved as f64 / guesses.len() as f64
+                );
+                results.push(guesses);
+            }
+            // The same estimate the difficulty tiers use
+            let difficulty: Vec<f64> = (0..matrix.answers().len())
+                .map(|i| results.iter().map(|g| g[i]).sum::<f64>() / results.len() as f64)
+                .collect();
+            let hardest = (0..difficulty.len())
+                .sorted_by(|&a, &b| difficulty[b].total_cmp(&difficulty[a]))
+                .take(10);
+            println!(
+                "Hardest for people: {}",
+                hardest
+                    .map(|i| format!("{} ({:.2})", matrix.answers()[i], difficulty[i]))
+                    .join(", ")
+            );
+            if let Some(path) = output {
+                let file =
+                    File::create(&path).with_context(|| format!("creating {}", path.display()))?;
+                simulate::write_csv(
+                    BufWriter::new(file),
+                    matrix.answers(),
+                    &results,
+                    &difficulty,
+                )
+                .with_context(|| format!("writing {}", path.display()))?;
+            }
+        }
+        Opt::Letters {
+            heatmap,
+            more_words,
+            game,
+        } => {
+            let dict = game.dictionary()?;
+            let words = dict.words(more_words).len();
+            let freq = dict.frequencies(more_words);
+            letters::display_letters(words, &freq, &dict.alphabet, heatmap);
+        }
+        Opt::Host { game } => {
+            let mut dict = game.dictionary()?;
+            let answers = host_answers(&mut dict)?;
+            let mut total = 0;
+            let mut scores = vec![];
+            for (round, &answer) in answers.iter().enumerate() {
+                println!("{}", Text::HostRound(round + 1, answers.len()));
+                let result = if game.peaks {
+                    play(
+                        answer,
+                        &dict,
+                        peaks_word,
+                        PlayOptions::default(),
+                        &mut Events::none(),
+                    )
+                } else {
+                    play(
+                        answer,
+                        &dict,
+                        guess_word,
+                        PlayOptions::default(),
+                        &mut Events::none(),
+                    )
+                };
+                // Running out of guesses scores one more than the last guess would have
+                let score = match result {
+                    Ok(guesses) => {
+                        println!("{}", Text::GoodJob(guesses));
+                        guesses
+                    }
+                    Err(_) => {
+                        println!("{}", Text::BetterLuck(answer));
+                        MAX_GUESSES + 1
+                    }
+                };
+                total += score;
+                scores.push(score);
+                println!("{}", Text::ScoreSoFar(total));
+            }
+            println!("{}", Text::FinalScore(total, &scores));
+        }
+        #[cfg(feature = "gui")]
+        Opt::Gui { game } => {
+            let dict = game.dictionary()?;
+            if game.peaks {
+                gui::run(dict, peaks_word)?
+            } else {
+                gui::run(dict, guess_word)?
+            }
+        }
+        Opt::Challenge(ChallengeOpt::Create { word, game }) => {
+            let dict = game.dictionary()?;
+            let word = match word {
+                Some(word) => dict.alphabet.parse_word(&word)?,
+                // Don't show the word in the error either, in case someone's watching
+                None => dict
+                    .alphabet
+                    .check_word(&prompt::read_secret("Secret word: ")?)
+                    .map_err(|e| anyhow!("The word {}", e))?,
+            };
+            println!("{}", challenge::encode(word));
+            println!("Send it to someone to play with `play --challenge CODE`.");
+        }
+        Opt::Cache(CacheOpt::Clear) => {
+            let count = cache::clear()?;
+            println!("Deleted {} cached files.", count);
+        }
+        Opt::Check { word, game } => {
+            let dict = game.dictionary()?;
+            let word = dict.alphabet.parse_word(&word)?;
+            if dict.answers.contains(&word) {
+                println!("\"{}\" is in the answer list.", word);
+            } else if dict.extra.contains(&word) {
+                println!(
+                    "\"{}\" is accepted as a guess, but can't be the answer.",
+                    word
+                );
+            } else {
+                println!("\"{}\" isn't in the word list.", word);
+            }
+        }
+        Opt::DiffLists {
+            old,
+            new,
+            old_extra,
+            new_extra,
+            alphabet,
+        } => {
+            let (old, new) = match (old_extra, new_extra) {
+                (Some(old_extra), Some(new_extra)) => (
+                    FileSource::new(&old, alphabet.clone()).with_extra(&old_extra),
+                    FileSource::new(&new, alphabet).with_extra(&new_extra),
+                ),
+                _ => (
+                    FileSource::new(&old, alphabet.clone()),
+                    FileSource::new(&new, alphabet),
+                ),
+            };
+            let (old_extra, new_extra) = (old.extra()?, new.extra()?);
+            let (old, new) = (old.answers()?, new.answers()?);
+            display_diff("Answers", &old, &new);
+            if !old_extra.is_empty() || !new_extra.is_empty() {
+                display_diff("Accepted words", &old_extra, &new_extra);
+
+                // Words that only moved between the lists are still accepted
+                let moved =
+                    |from: &[Word], to: &[Word]| from.iter().filter(|w| to.contains(w)).join(" ");
+                println!("Moved to the answers: {}", moved(&old_extra, &new));
+                println!("Moved out of the answers: {}", moved(&old, &new_extra));
+            }
+        }
+        Opt::Stats { profile } => {
+            let profile = Profile::open(&profile)?;
+            Stats::new(&profile.history()?).display();
+            let rows = profile.relay_rows()?;
+            if !rows.is_empty() {
+                let winning = rows
+                    .iter()
+                    .filter(|&&pattern| pattern == bench::SOLVED)
+                    .count();
+                println!(
+                    "Relay rows guessed: {}, winning guesses: {}",
+                    rows.len(),
+                    winning
+                );
+            }
+        }
+        Opt::Profiles(ProfilesOpt::List) => {
+            for profile in Profile::all()? {
+                println!("{:<20} {} games", profile.name, profile.history()?.len());
+            }
+        }
+        Opt::Profiles(ProfilesOpt::Delete { name }) => {
+            let profile = Profile::open(&name)?;
+            if !profile.exists()? {
+                return Err(anyhow!("There's no profile named \"{}\"", name));
+            }
+            profile.delete()?;
+            println!("Deleted profile \"{}\".", name);
+        }
+        Opt::Export {
+            format,
+            output,
+            profile,
+        } => {
+            let profiles = match profile {
+                Some(name) => vec![Profile::open(&name)?],
+                None => Profile::all()?,
+            };
+            match output {
+                Some(path) => {
+                    let file = File::create(&path)
+                        .with_context(|| format!("creating {}", path.display()))?;
+                    export::export(&profiles, format, BufWriter::new(file))
+                        .with_context(|| format!("writing {}", path.display()))?
+                }
+                None => export::export(&profiles, format, std::io::stdout().lock())?,
+            }
+        }
+        Opt::Leaderboard { tz } => {
+            let today = stats::todays_puzzle(tz);
+            let mut board = vec![];
+            for profile in Profile::all()? {
+                let stats = DailyStats::new(&profile.history()?, today);
+                if stats.played > 0 {
+                    board.push((profile.name, stats));
+                }
+            }
+            // Fewest guesses wins, and playing more days breaks ties
+            board.sort_by(|(_, a), (_, b)| {
+                a.mean_guesses
+                    .total_cmp(&b.mean_guesses)
+                    .then(b.played.cmp(&a.played))
+            });
+            println!("Rank Profile              Played  Won   Mean Streak  Max    Time");
+            for (rank, (name, stats)) in board.iter().enumerate() {
+                println!(
+                    "{:>4} {:<20} {:>6} {:>3}% {:>6.2} {:>6} {:>4} {:>6.0}s",
+                    rank + 1,
+                    name,
+                    stats.played,
+                    100 * stats.won / stats.played,
+                    stats.mean_guesses,
+                    stats.current_streak,
+                    stats.max_streak,
+                    stats.mean_time.as_secs_f64()
+                );
+            }
+        }
+        Opt::DailySummary { profile, tz } => {
+            let today = stats::todays_puzzle(tz);
+            let profiles = match profile {
+                Some(name) => vec![Profile::open(&name)?],
+                None => Profile::all()?,
+            };
+            let mut unplayed = 0;
+            for profile in profiles {
+                let history = profile.history()?;
+                let played = stats::daily_game(&history, today);
+                if played.is_none() {
+                    unplayed += 1;
+                }
+                println!(
+                    "{}: today (#{}) {}, yesterday {}",
+                    profile.name,
+                    today,
+                    daily_outcome(played),
+                    daily_outcome(
+                        today
+                            .checked_sub(1)
+                            .and_then(|p| stats::daily_game(&history, p))
+                    )
+                );
+            }
+            if unplayed > 0 {
+                std::process::exit(1);
+            }
+        }
+        Opt::Strategies => {
+            for strategy in Registry::default().iter() {
+                println!("{:<10} {}", strategy.name(), strategy.description());
             }
         }
     }
@@ -74,218 +1577,450 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn play(answer: Word) -> Result<usize> {
-    let mut results = vec![];
+/// How a daily puzzle went, for `daily-summary`
+fn daily_outcome(game: Option<&GameRecord>) -> String {
+    match game {
+        Some(GameRecord {
+            guesses: Some(guesses),
+            ..
+        }) => format!("solved in {}/{}", guesses, MAX_GUESSES),
+        Some(_) => format!("failed X/{}", MAX_GUESSES),
+        None => "not played".to_string(),
+    }
+}
 
-    let mut rl = rustyline::Editor::<()>::new();
-    for guess_no in 1..=MAX_GUESSES {
-        let guess = 'try_guess: loop {
-            let guess = rl.readline(format!("Guess {} of {}: ", guess_no, MAX_GUESSES).as_ref())?;
-            match Word::try_from(guess.as_str()) {
-                Err(_) => {
-                    println!("Invalid guess.");
-                    continue 'try_guess;
-                }
-                Ok(guess) => break guess,
+/// List the words added to and removed from a list
+fn display_diff(what: &str, old: &[Word], new: &[Word]) {
+    let old: BTreeSet<Word> = old.iter().copied().collect();
+    let new: BTreeSet<Word> = new.iter().copied().collect();
+    let added: Vec<&Word> = new.difference(&old).collect();
+    let removed: Vec<&Word> = old.difference(&new).collect();
+    println!("{}: {} before, {} after", what, old.len(), new.len());
+    println!("  Added ({}): {}", added.len(), added.iter().join(" "));
+    println!(
+        "  Removed ({}): {}",
+        removed.len(),
+        removed.iter().join(" ")
+    );
+}
+
+/// Ask the host for the secret words without echoing them, until they enter a blank line.
+/// Words that aren't in the word list are added to it, so they can be guessed.
+fn host_answers(dict: &mut Dictionary) -> Result<Vec<Word>> {
+    println!("{}", Text::EnterSecrets);
+    let mut answers = vec![];
+    loop {
+        let word = prompt::read_secret(&Text::SecretPrompt(answers.len() + 1).to_string())?;
+        if word.trim().is_empty() {
+            if answers.is_empty() {
+                return Err(anyhow!("No secret words were entered"));
             }
-        };
-        if guess == answer {
-            println!("Correct!  It was \"{}\"", answer);
-            return Ok(guess_no);
-        } else {
-            let gr = guess_word(guess, answer);
-            results.push(gr);
-            for (i, result) in results.iter().enumerate() {
-                println!("{}. {}", i, result);
+            return Ok(answers);
+        }
+        match dict.alphabet.check_word(&word) {
+            Ok(word) => {
+                dict.add_answer(word);
+                answers.push(word);
             }
+            // Don't show the word, since the guest may be watching
+            Err(e) => println!("{}", Text::SecretError(e)),
         }
     }
+}
 
-    Err(anyhow!("Ran out of guesses"))
+/// Read a list of letters to practice, which have to be in the alphabet
+fn parse_letters(alphabet: &Alphabet, letters: &str) -> Result<Vec<char>> {
+    let mut chars: Vec<char> = letters.chars().flat_map(char::to_lowercase).collect();
+    if let Some(c) = chars.iter().find(|&&c| !alphabet.contains(c)) {
+        return Err(anyhow!("'{}' isn't in the alphabet", c));
+    }
+    chars.sort_unstable();
+    chars.dedup();
+    if chars.is_empty() {
+        return Err(anyhow!("Give at least one letter to practice"));
+    }
+    Ok(chars)
 }
 
-fn random_answer() -> Word {
-    WORDS_USED
-        .get(rand::random::<usize>() % WORDS_USED.len())
-        .copied()
-        .unwrap()
-        .try_into()
-        .unwrap()
+fn open_events(target: Option<String>) -> Result<Events> {
+    match target {
+        Some(target) => Events::open(&target),
+        None => Ok(Events::none()),
+    }
 }
 
-fn solve(more_words: bool) -> Result<()> {
-    let mut omit_letters = vec![];
-    let mut req_letters = vec![];
-    let mut cand_letters = vec![];
-    let mut rl = rustyline::Editor::<()>::new();
-    let words: Vec<&str> = if more_words {
-        words::WORDS_USED
-            .iter()
-            .chain(words::WORDS_XTRA.iter())
-            .copied()
-            .collect()
+/// Name the strategies whose confidence intervals overlap the best one's, since the
+/// sample doesn't show which of them is really better
+fn display_indistinguishable(results: &[(&str, bench::Bench)]) {
+    let best = match results
+        .iter()
+        .min_by(|(_, a), (_, b)| a.mean_guesses().total_cmp(&b.mean_guesses()))
+    {
+        Some((name, result)) => (*name, result.confidence_interval()),
+        None => return,
+    };
+    let close: Vec<&str> = results
+        .iter()
+        .filter(|(name, result)| {
+            let (low, _) = result.confidence_interval();
+            *name != best.0 && low <= best.1 .1
+        })
+        .map(|(name, _)| *name)
+        .collect();
+    if close.is_empty() {
+        println!(
+            "{} is better than the rest, even allowing for the sample.",
+            best.0
+        );
+    } else {
+        println!(
+            "{} did best, but can't be told apart from {} on this sample.",
+            best.0,
+            close.join(", ")
+        );
+    }
+}
+
+/// A bar for autoplaying every answer, which only shows when there's a terminal to show it in
+fn progress_bar(matrix: &FeedbackMatrix, strategy: &str) -> ProgressBar {
+    let progress = ProgressBar::new(matrix.answers().len() as u64);
+    progress.set_style(
+        ProgressStyle::with_template("{prefix} [{bar:40}] {pos}/{len} answers, {eta} left")
+            .expect("valid template")
+            .progress_chars("=> "),
+    );
+    progress.set_prefix(strategy.to_string());
+    progress
+}
+
+/// The answer to a daily puzzle, from the official game when `online`, or else
+/// the schedule or built-in order. Either way, it's added to the answers.
+fn daily_answer(dict: &mut Dictionary, puzzle: u64, online: bool) -> Word {
+    let word = if online {
+        eprintln!(
+            "Spoiler warning: this shows the official answer to puzzle #{}.",
+            puzzle
+        );
+        online::official_answer(puzzle, &dict.alphabet).unwrap_or_else(|e| {
+            eprintln!("{:#}, so using the offline answer instead.", e);
+            dict.daily_answer(puzzle)
+        })
+    } else {
+        dict.daily_answer(puzzle)
+    };
+    dict.add_answer(word);
+    word
+}
+
+/// Benchmark every pairing of strategy and first guess at once, and rank them
+fn sweep(
+    matrix: &FeedbackMatrix,
+    alphabet: &Alphabet,
+    strategies: &[&dyn strategy::Strategy],
+    first_guesses: &[Word],
+    output: Option<PathBuf>,
+) -> Result<()> {
+    // Without first guesses, each strategy opens with its own choice
+    let first_guesses: Vec<Option<Word>> = if first_guesses.is_empty() {
+        vec![None]
     } else {
-        words::WORDS_USED.iter().copied().collect()
+        first_guesses.iter().copied().map(Some).collect()
     };
+    let progress = progress_bar(matrix, "sweep");
+    progress.set_length((matrix.answers().len() * strategies.len() * first_guesses.len()) as u64);
+    let mut results = bench::sweep(matrix, alphabet, strategies, &first_guesses, &progress);
+    progress.finish_and_clear();
+    results.sort_by(|(_, _, a), (_, _, b)| {
+        (a.failures(), a.mean_guesses())
+            .partial_cmp(&(b.failures(), b.mean_guesses()))
+            .unwrap()
+    });
 
-    let mut words: Vec<Word> = words
+    let labels: Vec<String> = results
         .iter()
-        .map(|&s| Word::try_from(s))
-        .map(Result::unwrap)
+        .map(|(strategy, first_guess, _)| match first_guess {
+            Some(word) => format!("{} {}", strategy.name(), word),
+            None => strategy.name().to_string(),
+        })
         .collect();
-    loop {
-        let (new_words, scores) = suggest(
-            Hint {
-                omit_letters: &omit_letters,
-                req_letters: &req_letters,
-                cand_letters: if cand_letters.is_empty() {
-                    None
-                } else {
-                    Some(&cand_letters)
-                },
-            },
-            &words,
+    let width = labels.iter().map(|l| l.len()).max().unwrap_or(0).max(8);
+    println!(
+        "{:>4} {:<width$} {:>6} {:>6} {:>5}",
+        "Rank", "Strategy", "Mean", "Failed", "Worst"
+    );
+    for (rank, (label, (_, _, result))) in labels.iter().zip(&results).enumerate() {
+        println!(
+            "{:>4} {:<width$} {:>6.3} {:>6} {:>5}",
+            rank + 1,
+            label,
+            result.mean_guesses(),
+            result.failures(),
+            result.worst()
         );
-        display_suggestions(&scores);
-        words = new_words;
-        'input: loop {
-            match rl.readline("Result: ") {
-                Ok(line) => {
-                    let mut position = 0;
-                    let mut negate_next = false;
-                    for c in line.chars() {
-                        match c {
-                            '!' | '`' | '\'' => negate_next = true,
-                            'a'..='z' => {
-                                if negate_next {
-                                    omit_letters.push(c);
-                                    negate_next = false;
-                                } else {
-                                    req_letters.push(c);
-                                    cand_letters.push(FoundLetter {
-                                        letter: c,
-                                        position,
-                                        correct_location: false,
-                                    })
-                                }
-                                position += 1;
-                            }
-                            'A'..='Z' => {
-                                req_letters.push(c.to_ascii_lowercase());
-                                cand_letters.push(FoundLetter {
-                                    letter: c.to_ascii_lowercase(),
-                                    position,
-                                    correct_location: true,
-                                });
-                                position += 1;
-                            }
-                            _ => {
-                                eprintln!("Invalid entry");
-                                continue 'input;
-                            }
-                        }
-                    }
-                    break;
-                }
-                Err(e) => {
-                    return Err(anyhow!("Error: {}", e));
-                }
-            }
+    }
+    if let Some(path) = output {
+        let results: Vec<(&str, &bench::Bench)> = labels
+            .iter()
+            .zip(&results)
+            .map(|(label, (_, _, result))| (label.as_str(), result))
+            .collect();
+        write_csv(&path, &results)?;
+    }
+    Ok(())
+}
+
+fn write_csv(path: &Path, results: &[(&str, &bench::Bench)]) -> Result<()> {
+    let file = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+    bench::write_csv(BufWriter::new(file), results)
+        .with_context(|| format!("writing {}", path.display()))
+}
+
+/// The built-in strategies, plus any scripted ones among the names
+fn strategy_registry(names: &[String]) -> Result<Registry> {
+    #[cfg_attr(not(feature = "script"), allow(unused_mut))]
+    let mut registry = Registry::default();
+    for name in names {
+        if let Some(path) = name.strip_prefix("script:") {
+            #[cfg(feature = "script")]
+            registry.register(script::ScriptStrategy::load(name, path.as_ref())?);
+            #[cfg(not(feature = "script"))]
+            return Err(anyhow!(
+                "Can't load {}: scripted strategies need the \"script\" feature",
+                path
+            ));
         }
     }
+    Ok(registry)
 }
 
-fn is_candidate(word: &Word, hint: &Hint) -> bool {
-    if !hint.omit_letters.is_empty() && word.0.iter().any(|c| hint.omit_letters.contains(c)) {
-        return false;
+/// What's played alongside the player's guesses, besides the game itself
+#[derive(Default)]
+struct PlayOptions<'a> {
+    /// The solver's guesses, shown beside the player's when racing
+    rival: &'a [Word],
+    coach: Option<Coach<'a>>,
+    relay: Option<&'a mut Relay>,
+    /// Ask before locking in each guess
+    confirm: bool,
+    /// Hide the guesses, counting down this many seconds before revealing the answer
+    streamer: Option<u64>,
+}
+
+fn play<L: Tile>(
+    answer: Word,
+    dict: &Dictionary,
+    feedback: fn(Word, Word) -> GuessResult<L>,
+    options: PlayOptions,
+    events: &mut Events,
+) -> Result<usize, WordleError> {
+    let PlayOptions {
+        rival,
+        mut coach,
+        mut relay,
+        confirm,
+        streamer,
+    } = options;
+    let mut game = Game::new(answer, feedback);
+    // Racing shows the solver's guess beside each of the player's
+    let rival: Vec<GuessResult<L>> = rival.iter().map(|&guess| feedback(guess, answer)).collect();
+    // Redraw the board in place when there's a screen to draw on, or else print it each turn
+    let redraw = std::io::stdout().is_terminal();
+
+    let draw = |game: &Game<L>| {
+        if redraw {
+            print!("\x1b[2J\x1b[H");
+        }
+        match streamer {
+            Some(_) => display_censored_board(game.results()),
+            None => display_board(game.results(), &rival),
+        }
+    };
+    // Revealing the answer shows the board as it really is
+    let reveal = |game: &Game<L>| {
+        if let Some(delay) = streamer {
+            reveal_countdown(delay);
+            display_board(game.results(), &rival);
+        }
+    };
+    draw(&game);
+    // The keyboard would give the guesses away
+    if streamer.is_none() {
+        display_keyboard(&dict.alphabet, game.results());
     }
-    if !hint.req_letters.is_empty() && !hint.req_letters.iter().all(|c| word.0.contains(c)) {
-        return false;
+
+    let mut rl = rustyline::Editor::new();
+    // Piped input can't be fixed before it's submitted, so it's checked afterwards instead
+    if std::io::stdin().is_terminal() {
+        rl.set_helper(Some(GuessHelper {
+            alphabet: &dict.alphabet,
+        }));
     }
-    // Now check all the positions
-    if let Some(cands) = hint.cand_letters {
-        for cand in cands {
-            if cand.correct_location {
-                if word.0[cand.position] != cand.letter {
-                    return false;
+    // The helper would turn down the answer to a confirmation, since it isn't a word
+    let mut confirm_rl = rustyline::Editor::<()>::new();
+    // The closest word to a guess that wasn't accepted, to fill in the next prompt with
+    let mut suggestion: Option<Word> = None;
+    while !game.is_over() {
+        let guess_no = game.guesses().len() + 1;
+        let prompt = match &relay {
+            // Whoever's turn it is leads the prompt
+            Some(relay) => format!(
+                "{}: {}",
+                relay.player(guess_no - 1),
+                Text::GuessPrompt(guess_no)
+            ),
+            None => Text::GuessPrompt(guess_no).to_string(),
+        };
+        let line = match suggestion.take() {
+            _ if streamer.is_some() => {
+                let line = prompt::read_secret(&prompt).map_err(anyhow::Error::from)?;
+                // Piped input reads as empty once it runs out
+                if line.is_empty() && !std::io::stdin().is_terminal() {
+                    return Err(WordleError::Interrupted);
+                }
+                line
+            }
+            Some(word) => rl.readline_with_initial(&prompt, (&word.to_string(), ""))?,
+            None => rl.readline(&prompt)?,
+        };
+        if line.trim() == "hint" {
+            // A letter of the answer would be on screen for everyone watching
+            if streamer.is_some() {
+                println!("{}", Text::NoHintStreaming);
+                continue;
+            }
+            match game.hint(dict) {
+                Some((position, letter)) => println!("{}", Text::Hint(position, letter)),
+                None => println!("{}", Text::NoHint),
+            }
+            continue;
+        }
+        let guess = match game.check(dict, &line) {
+            Ok(guess) => guess,
+            Err(e @ (GuessError::NotInList | GuessError::Word(WordError::Length(_)))) => {
+                let near = dict.nearest(&line, TYPO_SUGGESTIONS);
+                // Suggesting words would give away what was typed
+                if near.is_empty() || streamer.is_some() {
+                    println!("{}.", e);
+                    continue;
+                }
+                print!("{}. {}", e, Text::DidYouMean(&near));
+                // Only a terminal can fill the closest word in for confirming or editing
+                if std::io::stdin().is_terminal() {
+                    print!(" {}", Text::PressEnter(near[0]));
+                    suggestion = Some(near[0]);
+                }
+                println!();
+                continue;
+            }
+            Err(e) => {
+                println!("{}.", e);
+                continue;
+            }
+        };
+        if confirm {
+            let answer = confirm_rl.readline(&Text::ConfirmGuess(guess).to_string())?;
+            // "s" is yes in Spanish
+            if !matches!(answer.trim().to_lowercase().as_str(), "" | "y" | "s") {
+                // A terminal can fill the guess back in for correcting
+                if std::io::stdin().is_terminal() {
+                    suggestion = Some(guess);
                 }
-            } else if word.0[cand.position] == cand.letter {
-                return false;
+                continue;
+            }
+        }
+        game.submit(guess);
+        if let Some(relay) = &mut relay {
+            relay.push(game.results().last().unwrap().pattern());
+        }
+        events.guess_submitted(guess_no, guess)?;
+        events.feedback(guess, game.results().last().unwrap())?;
+        draw(&game);
+        if streamer.is_none() && !game.is_won() {
+            display_keyboard(&dict.alphabet, game.results());
+        }
+        // The winning guess is graded too, so the summary covers every row
+        if let Some(coach) = &mut coach {
+            let grade = coach.review(guess, answer);
+            events.guess_graded(guess_no, guess, grade)?;
+        }
+        if game.is_won() {
+            reveal(&game);
+            println!("{}", Text::Correct(answer));
+            if game.hints() > 0 {
+                let score = guess_no as f64 + game.hints() as f64 / 2.0;
+                println!("{}", Text::HintScore(game.hints(), score));
+            }
+            if let Some(coach) = &coach {
+                coach.summary();
             }
+            return Ok(guess_no);
         }
     }
-    true
+    if let Some(coach) = &coach {
+        coach.summary();
+    }
+    reveal(&game);
+
+    Err(WordleError::OutOfGuesses)
 }
 
 impl TryFrom<&str> for Word {
-    type Error = ();
+    type Error = WordError;
 
+    /// Reads a word regardless of surrounding whitespace, case, or whether accented
+    /// letters were typed as one character or as a letter and a combining accent
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        if value.len() != 5 {
-            return Err(());
+        let chars: Vec<char> = value.trim().nfc().flat_map(char::to_lowercase).collect();
+        if chars.len() != 5 {
+            return Err(WordError::Length(chars.len()));
+        }
+        if let Some(&c) = chars.iter().find(|c| c.is_whitespace() || c.is_control()) {
+            return Err(WordError::Symbol(c));
         }
 
         let mut word: Word = Word::default();
-        value
-            .chars()
-            .enumerate()
-            .take(5)
-            .for_each(|(pos, c)| word.0[pos] = c.to_ascii_lowercase());
-
+        word.0.copy_from_slice(&chars);
         Ok(word)
     }
 }
 
-fn suggest(hint: Hint, words: &[Word]) -> (Vec<Word>, BTreeMap<i32, Vec<Word>>) {
-    let mut freq = BTreeMap::new();
-
-    // Find the subset of possible matches based on the available hints
-    let words: Vec<Word> = words
-        .iter()
-        .filter(|&word| is_candidate(word, &hint))
-        .copied()
-        .collect();
+struct GuessResult<L = GuessLetter>([L; 5]);
 
-    // Determine the frequencies
-    words.iter().for_each(|word| {
-        word.0
+impl<L: Tile> GuessResult<L> {
+    /// Encode the feedback as a number, treating each tile as a base-3 digit
+    fn pattern(&self) -> u8 {
+        self.0
             .iter()
-            .for_each(|letter| *(freq.entry(letter).or_insert(0)) += 1)
-    });
-
-    // We really want this map to be ordered by highest score, but that requires
-    // implementing a wrapper type around numbers. It's easier to just negate the
-    // score so the map is ordered as desired.
-    let mut scores: BTreeMap<i32, Vec<Word>> = BTreeMap::new();
-    words.iter().for_each(|&word| {
-        scores
-            .entry(word.0.iter().unique().map(|c| -freq.get(&c).unwrap()).sum())
-            .or_insert_with(Vec::new)
-            .push(word)
-    });
-
-    (words, scores)
-}
-
-fn display_suggestions(scores: &BTreeMap<i32, Vec<Word>>) {
-    // Display the top suggestions
-    println!("Suggestions, in ascending order of score:");
-    for (score, words) in scores.iter().take(10).rev() {
-        println!("{:5} -> {:?}", -score, words);
+            .rev()
+            .fold(0, |acc, tile| acc * 3 + tile.code())
     }
 }
 
-struct GuessResult([GuessLetter; 5]);
-
-impl std::fmt::Display for GuessResult {
+impl<L: std::fmt::Display> std::fmt::Display for GuessResult<L> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for gc in self.0 {
+        for gc in &self.0 {
             write!(f, "{}", gc)?;
         }
         Ok(())
     }
 }
 
+/// Feedback for a single letter of a guess. Games are checked against words on
+/// several threads, so tiles have to be shareable between them.
+trait Tile: std::fmt::Display + Sync {
+    /// What this tile reveals about its letter for the keyboard display, if anything
+    fn key(&self) -> Option<GuessLetter>;
+
+    /// Which of the three kinds of feedback this is, with a correct letter always being 2
+    fn code(&self) -> u8;
+
+    /// What the tile says about its letter, in a word
+    fn name(&self) -> &'static str;
+
+    /// The colors the tile is drawn in, from the current theme
+    fn colors(&self) -> TileColors;
+}
+
 #[derive(Debug, Clone, Copy)]
 enum GuessLetter {
     Empty,
@@ -294,43 +2029,249 @@ enum GuessLetter {
     Incorrect(char),
 }
 
+fn write_tile(f: &mut impl Write, colors: TileColors, c: char) -> std::fmt::Result {
+    use owo_colors::OwoColorize;
+    let c = c.to_ascii_uppercase();
+    write!(f, "{}", c.color(colors.fg).on_color(colors.bg))
+}
+
+impl GuessLetter {
+    fn letter(&self) -> Option<char> {
+        match *self {
+            GuessLetter::Empty => None,
+            GuessLetter::Correct(c) | GuessLetter::Present(c) | GuessLetter::Incorrect(c) => {
+                Some(c)
+            }
+        }
+    }
+
+    /// How much the tile reveals, so the keyboard shows the most informative one
+    fn rank(&self) -> u8 {
+        match self {
+            GuessLetter::Empty => 0,
+            GuessLetter::Incorrect(_) => 1,
+            GuessLetter::Present(_) => 2,
+            GuessLetter::Correct(_) => 3,
+        }
+    }
+}
+
+impl Tile for GuessLetter {
+    fn key(&self) -> Option<GuessLetter> {
+        Some(*self)
+    }
+
+    fn code(&self) -> u8 {
+        match self {
+            GuessLetter::Empty | GuessLetter::Incorrect(_) => 0,
+            GuessLetter::Present(_) => 1,
+            GuessLetter::Correct(_) => 2,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            GuessLetter::Empty => "empty",
+            GuessLetter::Incorrect(_) => "absent",
+            GuessLetter::Present(_) => "present",
+            GuessLetter::Correct(_) => "correct",
+        }
+    }
+
+    fn colors(&self) -> TileColors {
+        let theme = Theme::current();
+        match self {
+            GuessLetter::Empty => theme.empty,
+            GuessLetter::Correct(_) => theme.correct,
+            GuessLetter::Present(_) => theme.present,
+            GuessLetter::Incorrect(_) => theme.absent,
+        }
+    }
+}
+
 impl std::fmt::Display for GuessLetter {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        use owo_colors::AnsiColors;
-        use owo_colors::DynColor;
+        write_tile(f, self.colors(), self.letter().unwrap_or(' '))
+    }
+}
+
+/// Feedback for the Wordle Peaks variant, which compares each letter of the
+/// guess against the answer's letter in the same position.
+#[derive(Debug, Clone, Copy)]
+enum PeaksLetter {
+    Empty,
+    Correct(char),
+    /// The answer's letter comes earlier in the alphabet
+    Earlier(char),
+    /// The answer's letter comes later in the alphabet
+    Later(char),
+}
+
+impl Tile for PeaksLetter {
+    // Peaks feedback says nothing about whether a letter appears elsewhere
+    fn key(&self) -> Option<GuessLetter> {
+        match *self {
+            PeaksLetter::Correct(c) => Some(GuessLetter::Correct(c)),
+            _ => None,
+        }
+    }
+
+    fn code(&self) -> u8 {
+        match self {
+            PeaksLetter::Empty | PeaksLetter::Earlier(_) => 0,
+            PeaksLetter::Later(_) => 1,
+            PeaksLetter::Correct(_) => 2,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            PeaksLetter::Empty => "empty",
+            PeaksLetter::Earlier(_) => "earlier",
+            PeaksLetter::Later(_) => "later",
+            PeaksLetter::Correct(_) => "correct",
+        }
+    }
+
+    fn colors(&self) -> TileColors {
+        let theme = Theme::current();
         match self {
-            GuessLetter::Empty => f.write_char(' '),
-            GuessLetter::Correct(c) => {
-                AnsiColors::Green.fmt_ansi_bg(f)?;
-                AnsiColors::Black.fmt_ansi_fg(f)?;
-                f.write_char(c.to_ascii_uppercase())
+            PeaksLetter::Empty => theme.empty,
+            PeaksLetter::Correct(_) => theme.correct,
+            PeaksLetter::Earlier(_) => theme.present,
+            PeaksLetter::Later(_) => theme.later,
+        }
+    }
+}
+
+impl std::fmt::Display for PeaksLetter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            PeaksLetter::Empty => write_tile(f, self.colors(), ' '),
+            PeaksLetter::Correct(c) | PeaksLetter::Earlier(c) | PeaksLetter::Later(c) => {
+                write_tile(f, self.colors(), c)
             }
-            GuessLetter::Present(c) => {
-                AnsiColors::Yellow.fmt_ansi_bg(f)?;
-                AnsiColors::Black.fmt_ansi_fg(f)?;
-                f.write_char(c.to_ascii_uppercase())
+        }
+    }
+}
+
+/// Show the board as the real game does, with a row for every guess allowed and the
+/// rival's results, if any, beside the rows played so far
+fn display_board<L: Tile>(results: &[GuessResult<L>], rival: &[GuessResult<L>]) {
+    for row in 0..MAX_GUESSES {
+        match (results.get(row), rival.get(row)) {
+            (Some(result), Some(rival)) => println!("{}   {}", result, rival),
+            (Some(result), None) => println!("{}", result),
+            (None, _) => {
+                let mut line = String::new();
+                for _ in 0..5 {
+                    write_tile(&mut line, Theme::current().empty, '·').unwrap();
+                }
+                println!("{}", line);
             }
-            GuessLetter::Incorrect(c) => {
-                AnsiColors::BrightBlack.fmt_ansi_bg(f)?;
-                AnsiColors::BrightWhite.fmt_ansi_fg(f)?;
-                f.write_char(c.to_ascii_uppercase())
+        }
+    }
+}
+
+/// Show the board with only the colors of the rows played, so someone watching
+/// can't read the guesses
+fn display_censored_board<L: Tile>(results: &[GuessResult<L>]) {
+    for row in 0..MAX_GUESSES {
+        let mut line = String::new();
+        for i in 0..5 {
+            match results.get(row) {
+                Some(result) => write_tile(&mut line, result.0[i].colors(), ' ').unwrap(),
+                None => write_tile(&mut line, Theme::current().empty, '·').unwrap(),
             }
-        }?;
-        AnsiColors::Black.fmt_ansi_bg(f)?;
-        AnsiColors::White.fmt_ansi_fg(f)
+        }
+        println!("{}", line);
+    }
+}
+
+/// Count down before the answer is shown, for anyone who'd rather look away
+fn reveal_countdown(delay: u64) {
+    for secs in (1..=delay).rev() {
+        print!("\r{}", Text::RevealIn(secs));
+        let _ = std::io::Write::flush(&mut std::io::stdout());
+        std::thread::sleep(Duration::from_secs(1));
     }
+    if delay > 0 {
+        println!();
+    }
+}
+
+/// Show every symbol of the alphabet, marked with the most informative feedback it has received
+fn display_keyboard<L: Tile>(alphabet: &Alphabet, results: &[GuessResult<L>]) {
+    display_keys(alphabet, &key_states(alphabet, results));
+}
+
+/// Show every symbol of the alphabet marked with its state, indexed by the alphabet
+fn display_keys(alphabet: &Alphabet, keys: &[GuessLetter]) {
+    let mut line = String::new();
+    for (&c, &key) in alphabet.symbols().iter().zip(keys) {
+        match key {
+            GuessLetter::Empty => line.push(c.to_ascii_uppercase()),
+            key => write!(line, "{}", key).unwrap(),
+        }
+    }
+    println!("{}", line);
+}
+
+/// The most each symbol of the alphabet has been revealed to be, indexed by the alphabet
+fn key_states<L: Tile>(alphabet: &Alphabet, results: &[GuessResult<L>]) -> Vec<GuessLetter> {
+    let mut keys = vec![GuessLetter::Empty; alphabet.len()];
+    for key in results
+        .iter()
+        .flat_map(|r| r.0.iter())
+        .filter_map(Tile::key)
+    {
+        if let Some(index) = key.letter().and_then(|c| alphabet.index(c)) {
+            if key.rank() > keys[index].rank() {
+                keys[index] = key;
+            }
+        }
+    }
+    keys
 }
 
 fn guess_word(guess: Word, answer: Word) -> GuessResult {
     let mut result = [GuessLetter::Empty; 5];
 
+    // Letters of the answer that aren't matched exactly. Each can only mark one
+    // letter of the guess as present, so repeated letters aren't over-reported.
+    let mut unmatched = [None; 5];
     for (pos, (&guess_char, &answer_char)) in guess.0.iter().zip(answer.0.iter()).enumerate() {
-        result[pos] = if guess_char == answer_char {
-            GuessLetter::Correct(guess_char)
-        } else if answer.0.iter().any(|&c| c == guess_char) {
-            GuessLetter::Present(guess_char)
+        if guess_char == answer_char {
+            result[pos] = GuessLetter::Correct(guess_char);
         } else {
-            GuessLetter::Incorrect(guess_char)
+            unmatched[pos] = Some(answer_char);
+        }
+    }
+
+    for (pos, &guess_char) in guess.0.iter().enumerate() {
+        if let GuessLetter::Correct(_) = result[pos] {
+            continue;
+        }
+        result[pos] = match unmatched.iter_mut().find(|c| **c == Some(guess_char)) {
+            Some(c) => {
+                *c = None;
+                GuessLetter::Present(guess_char)
+            }
+            None => GuessLetter::Incorrect(guess_char),
+        }
+    }
+
+    GuessResult(result)
+}
+
+fn peaks_word(guess: Word, answer: Word) -> GuessResult<PeaksLetter> {
+    let mut result = [PeaksLetter::Empty; 5];
+
+    for (pos, (&guess_char, &answer_char)) in guess.0.iter().zip(answer.0.iter()).enumerate() {
+        result[pos] = match answer_char.cmp(&guess_char) {
+            std::cmp::Ordering::Equal => PeaksLetter::Correct(guess_char),
+            std::cmp::Ordering::Less => PeaksLetter::Earlier(guess_char),
+            std::cmp::Ordering::Greater => PeaksLetter::Later(guess_char),
         }
     }
 
@@ -343,3 +2284,37 @@ fn test_guess_word() {
     let answer = "bacfe".try_into().unwrap();
     eprintln!("{}", guess_word(guess, answer));
 }
+
+#[test]
+fn test_guess_word_repeated_letters() {
+    let result = guess_word("speed".try_into().unwrap(), "crepe".try_into().unwrap());
+    assert_eq!(
+        result.0.map(|tile| tile.code()),
+        [0, 1, 2, 1, 0],
+        "{}",
+        result
+    );
+    let result = guess_word("eerie".try_into().unwrap(), "abide".try_into().unwrap());
+    assert_eq!(result.0.map(|tile| tile.code()), [0, 0, 0, 1, 2]);
+}
+
+#[test]
+fn test_word_errors() {
+    let alphabet = Alphabet::letters();
+    assert_eq!(Word::try_from(" Crane\n"), Word::try_from("crane"));
+    // "é" as an "e" followed by a combining accent
+    assert!(Word::try_from("cafe\u{301}s").is_ok());
+    assert_eq!(alphabet.check_word("cran"), Err(WordError::Length(4)));
+    assert_eq!(alphabet.check_word("cr4ne"), Err(WordError::Digit('4')));
+    assert_eq!(alphabet.check_word("cr-ne"), Err(WordError::Symbol('-')));
+    assert_eq!(alphabet.check_word("cr ne"), Err(WordError::Symbol(' ')));
+}
+
+#[test]
+fn test_alphabet_spec() {
+    let alphabet: Alphabet = "a-z'".parse().unwrap();
+    assert_eq!(alphabet.len(), 27);
+    assert!(alphabet.parse_word("can't").is_ok());
+    assert!(alphabet.parse_word("ca-nt").is_err());
+    assert!("z-a".parse::<Alphabet>().is_err());
+}
@@ -0,0 +1,96 @@
+use crate::dictionary::Alphabet;
+use crate::{cache, stats, Word};
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Where the official game publishes each day's puzzle, as `<date>.json`
+const PUZZLE_URL: &str = "https://www.nytimes.com/svc/wordle/v2";
+
+/// What cached official answers are called. Answers never change once published,
+/// so each puzzle is only ever fetched once.
+const ANSWER_CACHE: &str = "official";
+
+/// What the time of the last request is kept as in the cache
+const REQUEST_CACHE: &str = "official-request";
+
+/// The least time between requests, so scripts calling this in a loop don't
+/// hammer the official server
+const MIN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long to wait for the server before falling back to the offline answer
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The answer to a daily puzzle from the official game, from the cache if it's been
+/// fetched before
+pub fn official_answer(puzzle: u64, alphabet: &Alphabet) -> Result<Word> {
+    // Each symbol is kept as 4 bytes, so accented answers fit too
+    if let Some(bytes) = cache::load(ANSWER_CACHE, puzzle, 20) {
+        let mut word = Word::default();
+        for (c, bytes) in word.0.iter_mut().zip(bytes.chunks(4)) {
+            let code = u32::from_le_bytes(bytes.try_into().expect("4 bytes"));
+            *c = char::from_u32(code).ok_or_else(|| anyhow!("The cached answer is damaged"))?;
+        }
+        return Ok(word);
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    if let Some(bytes) = cache::load(REQUEST_CACHE, 0, 8) {
+        let last = u64::from_le_bytes(bytes.try_into().expect("8 bytes"));
+        let wait = (last + MIN_INTERVAL.as_secs()).saturating_sub(now);
+        if wait > 0 {
+            return Err(anyhow!(
+                "The official game was asked too recently (try again in {}s)",
+                wait
+            ));
+        }
+    }
+    cache::save(REQUEST_CACHE, 0, &now.to_le_bytes())?;
+
+    let url = format!("{}/{}.json", PUZZLE_URL, stats::puzzle_date(puzzle));
+    let body = ureq::get(&url)
+        .timeout(TIMEOUT)
+        .call()
+        .context("Couldn't fetch the official answer")?
+        .into_string()
+        .with_context(|| format!("reading {}", url))?;
+    let word = parse_puzzle(&body, puzzle, alphabet).with_context(|| format!("reading {}", url))?;
+
+    let bytes: Vec<u8> = word
+        .0
+        .iter()
+        .flat_map(|&c| (c as u32).to_le_bytes())
+        .collect();
+    cache::save(ANSWER_CACHE, puzzle, &bytes)?;
+    Ok(word)
+}
+
+/// The answer in the official game's description of a puzzle, e.g.
+/// `{"solution": "cigar", "days_since_launch": 0, ...}`
+fn parse_puzzle(body: &str, puzzle: u64, alphabet: &Alphabet) -> Result<Word> {
+    let json: Value = serde_json::from_str(body)?;
+    if let Some(number) = json["days_since_launch"].as_u64() {
+        if number != puzzle {
+            return Err(anyhow!("Got puzzle #{} instead of #{}", number, puzzle));
+        }
+    }
+    let solution = json["solution"]
+        .as_str()
+        .ok_or_else(|| anyhow!("There's no solution"))?;
+    Ok(alphabet.parse_word(solution)?)
+}
+
+#[test]
+fn test_parse_puzzle() {
+    let alphabet = Alphabet::letters();
+    let body =
+        r#"{"id": 1, "solution": "cigar", "print_date": "2021-06-19", "days_since_launch": 0}"#;
+    assert_eq!(
+        parse_puzzle(body, 0, &alphabet).unwrap().to_string(),
+        "cigar"
+    );
+    assert!(parse_puzzle(body, 1, &alphabet).is_err());
+    assert!(parse_puzzle("{}", 0, &alphabet).is_err());
+}
@@ -0,0 +1,70 @@
+use crate::dictionary::Alphabet;
+use owo_colors::OwoColorize;
+use rustyline::completion::Completer;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
+use rustyline::Helper;
+use std::borrow::Cow;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Commands that can be entered at the Play prompt instead of a guess
+pub const PLAY_COMMANDS: &[&str] = &["hint"];
+
+/// Checks guesses as they're typed at the Play prompt, so only a word of the
+/// alphabet's symbols (or a command) can be submitted, and shows the input in red
+/// until it is one
+pub struct GuessHelper<'a> {
+    pub alphabet: &'a Alphabet,
+}
+
+impl Validator for GuessHelper<'_> {
+    fn validate(&self, ctx: &mut ValidationContext) -> rustyline::Result<ValidationResult> {
+        if PLAY_COMMANDS.contains(&ctx.input().trim()) {
+            return Ok(ValidationResult::Valid(None));
+        }
+        Ok(match self.alphabet.check_word(ctx.input()) {
+            Ok(_) => ValidationResult::Valid(None),
+            Err(e) => ValidationResult::Invalid(Some(format!("  ({})", e))),
+        })
+    }
+}
+
+impl Highlighter for GuessHelper<'_> {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        match self.alphabet.check_word(line) {
+            _ if PLAY_COMMANDS.contains(&line.trim()) => Cow::Borrowed(line),
+            Ok(_) => Cow::Borrowed(line),
+            Err(_) => Cow::Owned(line.red().to_string()),
+        }
+    }
+
+    // Any change can make the input valid or invalid
+    fn highlight_char(&self, _line: &str, _pos: usize) -> bool {
+        true
+    }
+}
+
+impl Completer for GuessHelper<'_> {
+    type Candidate = String;
+}
+
+impl Hinter for GuessHelper<'_> {
+    type Hint = String;
+}
+
+impl Helper for GuessHelper<'_> {}
+
+/// Read a secret word someone types at a shared terminal, without showing it on
+/// screen or keeping it in any history. Piped input is read as a plain line, and
+/// reads as blank once it runs out.
+pub fn read_secret(prompt: &str) -> io::Result<String> {
+    if io::stdin().is_terminal() {
+        return rpassword::prompt_password(prompt);
+    }
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    Ok(line)
+}
@@ -0,0 +1,121 @@
+use crate::bench::SOLVED;
+use crate::i18n::Text;
+use crate::stats::{GameRecord, Profile};
+use crate::{Word, WordleError};
+use anyhow::Result;
+use std::time::Duration;
+
+/// Players taking turns to guess on the same board in `play --relay`, the first
+/// player guessing first
+pub struct Relay {
+    /// Each player's stats are recorded in the profile with their name
+    players: Vec<Profile>,
+    /// The feedback for each row played so far
+    rows: Vec<u8>,
+}
+
+impl Relay {
+    /// Ask for the players' names in the order they'll guess, until a blank line
+    pub fn ask_players() -> Result<Self, WordleError> {
+        println!("{}", Text::EnterPlayers);
+        let mut rl = rustyline::Editor::<()>::new();
+        let mut players: Vec<Profile> = vec![];
+        loop {
+            let line = rl.readline(&Text::PlayerPrompt(players.len() + 1).to_string())?;
+            let name = line.trim();
+            if name.is_empty() {
+                if players.len() >= 2 {
+                    return Ok(Relay::new(players));
+                }
+                println!("{}", Text::TooFewPlayers);
+                continue;
+            }
+            match Profile::open(name) {
+                Ok(_) if players.iter().any(|p| p.name == name) => {
+                    println!("{}", Text::SamePlayer(name))
+                }
+                Ok(player) => players.push(player),
+                Err(e) => println!("{}", e),
+            }
+        }
+    }
+
+    fn new(players: Vec<Profile>) -> Self {
+        Relay {
+            players,
+            rows: vec![],
+        }
+    }
+
+    /// The name of whoever guesses the row, counting from 0
+    pub fn player(&self, row: usize) -> &str {
+        &self.players[row % self.players.len()].name
+    }
+
+    /// Add the feedback for the row the current player guessed
+    pub fn push(&mut self, pattern: u8) {
+        self.rows.push(pattern);
+    }
+
+    /// Who made the winning guess, if someone did
+    pub fn winner(&self) -> Option<&str> {
+        match self.rows.last() {
+            Some(&SOLVED) => Some(self.player(self.rows.len() - 1)),
+            _ => None,
+        }
+    }
+
+    /// The grid of colored squares to share, with who guessed each row beside it
+    pub fn grid(&self) -> String {
+        self.rows
+            .iter()
+            .enumerate()
+            .map(|(row, &pattern)| format!("{} {}\n", squares(pattern), self.player(row)))
+            .collect()
+    }
+
+    /// Record the game in each player's profile, along with the rows they guessed
+    pub fn record(&self, answer: Word, guesses: Option<usize>, elapsed: Duration) -> Result<()> {
+        let game = GameRecord::new("relay", answer, guesses, elapsed);
+        for (i, player) in self.players.iter().enumerate() {
+            let rows: Vec<u8> = self
+                .rows
+                .iter()
+                .skip(i)
+                .step_by(self.players.len())
+                .copied()
+                .collect();
+            player.record(&game)?;
+            player.record_relay_rows(game.time, &rows)?;
+        }
+        Ok(())
+    }
+}
+
+/// A row of a shared grid, as a colored square for each tile
+pub fn squares(mut pattern: u8) -> String {
+    (0..5)
+        .map(|_| {
+            let square = ['⬛', '🟨', '🟩'][(pattern % 3) as usize];
+            pattern /= 3;
+            square
+        })
+        .collect()
+}
+
+#[test]
+fn test_relay() {
+    let players = ["ann", "bo", "cy"]
+        .iter()
+        .map(|name| Profile::open(name).unwrap())
+        .collect();
+    let mut relay = Relay::new(players);
+    for pattern in [9, 1 + 2 * 9, 100, SOLVED] {
+        relay.push(pattern);
+    }
+    assert_eq!(relay.player(4), "bo");
+    assert_eq!(relay.winner(), Some("ann"));
+    assert_eq!(relay.grid().lines().next(), Some("⬛⬛🟨⬛⬛ ann"));
+    relay.rows.pop();
+    assert_eq!(relay.winner(), None);
+}
@@ -0,0 +1,183 @@
+use crate::bench::{FeedbackMatrix, SOLVED};
+use crate::{guess_word, Word};
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use std::collections::HashMap;
+
+/// The most matching guesses listed for each row of the grid
+const ROW_WORDS: usize = 12;
+
+/// Read a row of a shared grid, as colored squares or as g, y and b (or .) for
+/// each tile, as the feedback pattern it stands for
+pub fn parse_row(row: &str) -> Result<u8> {
+    let codes: Vec<u8> = row
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c.to_ascii_lowercase() {
+            // High contrast grids use orange and blue for green and yellow
+            'g' | '2' | '🟩' | '🟧' => Ok(2),
+            'y' | '1' | '🟨' | '🟦' => Ok(1),
+            'b' | '.' | '0' | '⬛' | '⬜' => Ok(0),
+            c => Err(anyhow!("\"{}\" isn't a tile in row \"{}\"", c, row)),
+        })
+        .collect::<Result<_>>()?;
+    if codes.len() != 5 {
+        return Err(anyhow!("Row \"{}\" should have 5 tiles", row));
+    }
+    Ok(codes.iter().rev().fold(0, |acc, code| acc * 3 + code))
+}
+
+/// Write a feedback pattern as g, y and b for each tile
+pub fn format_row(mut pattern: u8) -> String {
+    (0..5)
+        .map(|_| {
+            let tile = ['b', 'y', 'g'][(pattern % 3) as usize];
+            pattern /= 3;
+            tile
+        })
+        .collect()
+}
+
+/// Check the feedback given for each guess against what the answer really gives,
+/// returning the turns that were scored wrongly with the feedback they should have had
+pub fn mismatches(answer: Word, turns: &[(Word, u8)]) -> Vec<(usize, u8)> {
+    turns
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &(guess, given))| {
+            let actual = guess_word(guess, answer).pattern();
+            (actual != given).then_some((i, actual))
+        })
+        .collect()
+}
+
+/// Find guesses that would give each row of a finished game's grid, for working
+/// back from the colors to the words, like a Crosswordle puzzle
+pub fn reverse(
+    matrix: &FeedbackMatrix,
+    answer: usize,
+    rows: &[u8],
+    sequences: usize,
+) -> Result<()> {
+    if let Some(row) = rows.iter().rev().skip(1).position(|&p| p == SOLVED) {
+        return Err(anyhow!(
+            "Row {} is all green, so the game would have ended there",
+            rows.len() - 1 - row
+        ));
+    }
+    let matches = row_matches(matrix, answer, rows);
+    for (i, words) in matches.iter().enumerate() {
+        println!(
+            "Row {}: {} {}{}{}",
+            i + 1,
+            words.len(),
+            if words.len() == 1 { "guess" } else { "guesses" },
+            if words.is_empty() { "" } else { ", e.g. " },
+            words
+                .iter()
+                .take(ROW_WORDS)
+                .map(|&guess| matrix.guesses()[guess])
+                .join(", ")
+        );
+    }
+
+    let found = find_sequences(&matches, sequences);
+    if found.is_empty() {
+        println!("No sequence of different guesses gives this grid.");
+        return Ok(());
+    }
+    println!(
+        "{} sequences of different guesses give this grid, such as:",
+        count_sequences(rows, &matches)
+    );
+    for sequence in found {
+        println!(
+            "  {}",
+            sequence.iter().map(|&g| matrix.guesses()[g]).join(" → ")
+        );
+    }
+    Ok(())
+}
+
+/// The guesses giving each row's pattern for the answer, as indices into the matrix
+fn row_matches(matrix: &FeedbackMatrix, answer: usize, rows: &[u8]) -> Vec<Vec<usize>> {
+    rows.iter()
+        .map(|&pattern| {
+            (0..matrix.guesses().len())
+                .filter(|&guess| matrix.pattern(guess, answer) == pattern)
+                .collect()
+        })
+        .collect()
+}
+
+/// How many ways there are to fill the rows without repeating a guess. Rows with
+/// different patterns never share a guess, so only rows with the same pattern
+/// have to choose different ones.
+fn count_sequences(rows: &[u8], matches: &[Vec<usize>]) -> u128 {
+    let mut repeats: HashMap<u8, usize> = HashMap::new();
+    rows.iter()
+        .zip(matches)
+        .map(|(&pattern, words)| {
+            let earlier = repeats.entry(pattern).or_default();
+            let ways = words.len().saturating_sub(*earlier) as u128;
+            *earlier += 1;
+            ways
+        })
+        .product()
+}
+
+/// Up to `limit` ways of filling the rows without repeating a guess, varying the
+/// earliest rows first so the examples differ in more than the last guess
+fn find_sequences(matches: &[Vec<usize>], limit: usize) -> Vec<Vec<usize>> {
+    let mut found = vec![];
+    for offset in 0..limit {
+        let mut sequence: Vec<usize> = vec![];
+        for words in matches {
+            let mut choices = words.iter().filter(|g| !sequence.contains(g));
+            let choice = match choices.clone().nth(offset).or_else(|| choices.next()) {
+                Some(&choice) => choice,
+                None => return found,
+            };
+            sequence.push(choice);
+        }
+        if !found.contains(&sequence) {
+            found.push(sequence);
+        }
+    }
+    found
+}
+
+#[test]
+fn test_reverse() {
+    use crate::bench::test_matrix;
+    let matrix = test_matrix();
+    assert_eq!(parse_row("🟩🟩🟩🟩🟩").unwrap(), SOLVED);
+    assert_eq!(parse_row("b.ybg").unwrap(), 2 * 81 + 9);
+    assert!(parse_row("gggg").is_err());
+
+    // "humph", then "cigar" and "awake" in either order, against "blush"
+    let rows = ["bybbg", "bbbbb", "bbbbb", "ggggg"].map(|row| parse_row(row).unwrap());
+    let matches = row_matches(&matrix, 5, &rows);
+    assert_eq!(matches, [vec![3], vec![0, 4], vec![0, 4], vec![5]]);
+    assert_eq!(count_sequences(&rows, &matches), 2);
+    assert_eq!(
+        find_sequences(&matches, 3),
+        [vec![3, 0, 4, 5], vec![3, 4, 0, 5]]
+    );
+    assert!(reverse(&matrix, 5, &[SOLVED, SOLVED], 3).is_err());
+}
+
+#[test]
+fn test_mismatches() {
+    let word = |w: &str| -> Word { w.try_into().unwrap() };
+    let turns = [
+        (word("speed"), parse_row("bbyby").unwrap()),
+        // The second E is gray, since "abide" has only one
+        (word("geese"), parse_row("bygbg").unwrap()),
+        (word("abide"), parse_row("ggggg").unwrap()),
+    ];
+    let wrong = mismatches(word("abide"), &turns);
+    assert_eq!(wrong.len(), 1);
+    assert_eq!(wrong[0].0, 1);
+    assert_eq!(format_row(wrong[0].1), "bbbbg");
+}
@@ -0,0 +1,99 @@
+use crate::dictionary::Alphabet;
+use crate::stats;
+use crate::Word;
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The schedule file looked for in the `wordle_solve` config directory
+const SCHEDULE_FILENAME: &str = "schedule.txt";
+
+/// The answers to the daily puzzles as the official game has them, for anyone who
+/// has that list. Puzzles it doesn't cover fall back to the built-in order.
+#[derive(Debug, Default)]
+pub struct Schedule(BTreeMap<u64, Word>);
+
+impl Schedule {
+    /// Read the schedule at the path, or else the one in the config directory if
+    /// there is one there
+    pub fn load(path: Option<&Path>, alphabet: &Alphabet) -> Result<Option<Self>> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match default_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(None),
+            },
+        };
+        let text =
+            fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        Schedule::parse(&text, alphabet)
+            .with_context(|| format!("reading {}", path.display()))
+            .map(Some)
+    }
+
+    /// Each line is a word, optionally after the date it's the answer on (e.g.
+    /// "2021-06-19 cigar") or its puzzle number. A word on its own is the answer to
+    /// the puzzle after the line before's, so a plain list starts at puzzle 0.
+    pub fn parse(text: &str, alphabet: &Alphabet) -> Result<Self> {
+        let mut schedule = Schedule::default();
+        let mut puzzle = 0;
+        for (line_no, line) in text.lines().enumerate() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let word = match fields[..] {
+                [] => continue,
+                [word] => word,
+                [when, word] => {
+                    puzzle = match NaiveDate::parse_from_str(when, "%Y-%m-%d") {
+                        Ok(date) => stats::puzzle_on(date),
+                        Err(_) => when.parse().map_err(|_| {
+                            anyhow!(
+                                "line {}: \"{}\" isn't a date or puzzle number",
+                                line_no + 1,
+                                when
+                            )
+                        })?,
+                    };
+                    word
+                }
+                _ => return Err(anyhow!("line {}: expected a date and a word", line_no + 1)),
+            };
+            let word = alphabet
+                .parse_word(word)
+                .with_context(|| format!("line {}", line_no + 1))?;
+            schedule.0.insert(puzzle, word);
+            puzzle += 1;
+        }
+        Ok(schedule)
+    }
+
+    pub fn get(&self, puzzle: u64) -> Option<Word> {
+        self.0.get(&puzzle).copied()
+    }
+}
+
+fn default_path() -> Option<PathBuf> {
+    Some(
+        dirs::config_dir()?
+            .join("wordle_solve")
+            .join(SCHEDULE_FILENAME),
+    )
+}
+
+#[test]
+fn test_schedule() {
+    let alphabet = Alphabet::letters();
+    let schedule = Schedule::parse(
+        "cigar\nrebut\n\n2021-06-25 humph\n9 blush\nfocal\n",
+        &alphabet,
+    )
+    .unwrap();
+    let word = |puzzle| schedule.get(puzzle).map(|w| w.to_string());
+    assert_eq!(word(0).as_deref(), Some("cigar"));
+    assert_eq!(word(1).as_deref(), Some("rebut"));
+    assert_eq!(word(2), None);
+    assert_eq!(word(6).as_deref(), Some("humph"));
+    assert_eq!(word(10).as_deref(), Some("focal"));
+    assert!(Schedule::parse("someday cigar", &alphabet).is_err());
+}
@@ -0,0 +1,159 @@
+use crate::bench::FeedbackMatrix;
+use crate::dictionary::Alphabet;
+use crate::strategy::{Frequencies, Frequency, Strategy};
+use crate::Word;
+use anyhow::{anyhow, Result};
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use std::path::Path;
+
+/// A strategy that scores guesses with a Rhai script, for prototyping heuristics without
+/// recompiling. The script defines `scores(guesses, candidates, stats)`, which is called
+/// once per turn and returns a score for each guess. The highest score is guessed, or
+/// the frequency strategy's choice on a turn the script fails.
+///
+/// `guesses` and `candidates` are arrays of words, and `stats` is a map with `freq`, how
+/// often each letter appears in the candidates, and `positions`, an array with the same
+/// counts for each position.
+pub struct ScriptStrategy {
+    name: String,
+    engine: Engine,
+    ast: AST,
+}
+
+impl ScriptStrategy {
+    pub fn load(name: &str, path: &Path) -> Result<Self> {
+        let mut engine = Engine::new();
+        // Debug builds of Rhai only allow half the nesting of release builds, which is
+        // too little for loops within loops in scores()
+        engine.set_max_expr_depths(64, 32);
+        let ast = engine
+            .compile_file(path.into())
+            .map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+        if !ast.iter_functions().any(|f| f.name == "scores") {
+            return Err(anyhow!("{} doesn't define scores()", path.display()));
+        }
+
+        Ok(ScriptStrategy {
+            name: name.to_string(),
+            engine,
+            ast,
+        })
+    }
+}
+
+impl Strategy for ScriptStrategy {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        "Score guesses with a script"
+    }
+
+    fn choose(&self, matrix: &FeedbackMatrix, alphabet: &Alphabet, candidates: &[usize]) -> usize {
+        let scores = match self.scores(matrix, alphabet, candidates) {
+            Ok(scores) => scores,
+            Err(e) => {
+                eprintln!(
+                    "{}: {}. Using the frequency strategy this turn.",
+                    self.name, e
+                );
+                return Frequency.choose(matrix, alphabet, candidates);
+            }
+        };
+
+        // Prefer guesses that could be the answer when the scores tie
+        let mut is_candidate = vec![false; matrix.guesses().len()];
+        candidates.iter().for_each(|&c| is_candidate[c] = true);
+        let mut best = (f64::NEG_INFINITY, false, 0);
+        for (guess, score) in scores.into_iter().enumerate() {
+            if (score, is_candidate[guess]) > (best.0, best.1) {
+                best = (score, is_candidate[guess], guess);
+            }
+        }
+        best.2
+    }
+}
+
+impl ScriptStrategy {
+    /// Run the script's `scores()`, checking it gave a number for every guess
+    fn scores(
+        &self,
+        matrix: &FeedbackMatrix,
+        alphabet: &Alphabet,
+        candidates: &[usize],
+    ) -> Result<Vec<f64>> {
+        let words: Vec<Word> = candidates.iter().map(|&c| matrix.guesses()[c]).collect();
+        let guesses: Array = matrix.guesses().iter().map(to_dynamic).collect();
+        let scores: Array = self
+            .engine
+            .call_fn(
+                &mut Scope::new(),
+                &self.ast,
+                "scores",
+                (
+                    guesses,
+                    words.iter().map(to_dynamic).collect::<Array>(),
+                    stats(&matrix.frequencies(alphabet, candidates), alphabet),
+                ),
+            )
+            .map_err(|e| anyhow!("{}", e))?;
+        if scores.len() != matrix.guesses().len() {
+            return Err(anyhow!(
+                "scores() returned {} scores for {} guesses",
+                scores.len(),
+                matrix.guesses().len()
+            ));
+        }
+        scores
+            .iter()
+            .map(|score| {
+                score
+                    .as_float()
+                    .or_else(|_| score.as_int().map(|i| i as f64))
+                    .map_err(|t| anyhow!("scores must be numbers, not {}", t))
+            })
+            .collect()
+    }
+}
+
+fn to_dynamic(word: &Word) -> Dynamic {
+    word.to_string().into()
+}
+
+/// Letter counts among the candidates, overall and by position
+fn stats(freq: &Frequencies, alphabet: &Alphabet) -> Map {
+    let to_map = |freq: &[i32]| -> Map {
+        alphabet
+            .symbols()
+            .iter()
+            .zip(freq)
+            .map(|(c, &n)| (c.to_string().into(), Dynamic::from_int(n.into())))
+            .collect()
+    };
+
+    let positions: Array = freq
+        .positions
+        .iter()
+        .map(|freq| to_map(freq).into())
+        .collect();
+
+    let mut stats = Map::new();
+    stats.insert("freq".into(), to_map(&freq.letters).into());
+    stats.insert("positions".into(), positions.into());
+    stats
+}
+
+#[test]
+fn test_frequency_script() {
+    let matrix = crate::bench::test_matrix();
+    let alphabet = Alphabet::letters();
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("scripts/frequency.rhai");
+    let script = ScriptStrategy::load("script:frequency", &path).unwrap();
+    for candidates in [vec![0, 1, 2, 3, 4, 5], vec![1, 3, 5], vec![4]] {
+        assert_eq!(
+            script.choose(&matrix, &alphabet, &candidates),
+            Frequency.choose(&matrix, &alphabet, &candidates)
+        );
+    }
+}
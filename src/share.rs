@@ -0,0 +1,79 @@
+use crate::bench::SOLVED;
+use crate::reverse::parse_row;
+use crate::MAX_GUESSES;
+use anyhow::{anyhow, Result};
+
+/// A game as it's shared after playing, e.g. "Wordle 1,234 4/6" and a grid of
+/// colored squares
+#[derive(Debug, PartialEq, Eq)]
+pub struct Share {
+    pub puzzle: u64,
+    /// How many guesses it took, or `None` for a game that wasn't solved
+    pub guesses: Option<usize>,
+    pub rows: Vec<u8>,
+}
+
+impl Share {
+    /// Read pasted share text. Lines other than the title and the grid, such as a
+    /// link to the game, are ignored.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        let title = lines
+            .next()
+            .ok_or_else(|| anyhow!("The share text is empty"))?;
+        let (puzzle, guesses) =
+            parse_title(title).ok_or_else(|| anyhow!("\"{}\" isn't a share title", title))?;
+        let rows: Vec<u8> = lines
+            .filter(|line| !line.chars().any(|c| c.is_ascii_alphanumeric()))
+            .map(parse_row)
+            .collect::<Result<_>>()?;
+
+        let solved = rows.last() == Some(&SOLVED);
+        match guesses {
+            Some(guesses) if guesses != rows.len() || !solved => Err(anyhow!(
+                "The title says {} guesses, but the grid has {} rows{}",
+                guesses,
+                rows.len(),
+                if solved { "" } else { " and isn't solved" }
+            )),
+            None if solved || rows.len() != MAX_GUESSES => Err(anyhow!(
+                "The title says it wasn't solved, but the grid doesn't show {} misses",
+                MAX_GUESSES
+            )),
+            _ => Ok(Share {
+                puzzle,
+                guesses,
+                rows,
+            }),
+        }
+    }
+}
+
+/// The puzzle number and score from a title like "Wordle 1,234 4/6*", where the
+/// score is X for a game that wasn't solved, and * marks hard mode
+fn parse_title(title: &str) -> Option<(u64, Option<usize>)> {
+    let mut words = title.split_whitespace().skip(1);
+    let puzzle = words.next()?.replace(',', "").parse().ok()?;
+    let (score, _) = words.next()?.split_once('/')?;
+    let guesses = match score {
+        "X" | "x" => None,
+        score => Some(score.parse().ok()?),
+    };
+    Some((puzzle, guesses))
+}
+
+#[test]
+fn test_share() {
+    let share = Share::parse(
+        "Wordle 1,234 3/6*\n\n⬛⬛🟨⬛⬛\n🟩⬛🟩🟨⬛\n🟩🟩🟩🟩🟩\nhttps://example.com",
+    )
+    .unwrap();
+    assert_eq!(share.puzzle, 1234);
+    assert_eq!(share.guesses, Some(3));
+    assert_eq!(share.rows.len(), 3);
+    assert_eq!(share.rows[0], 9);
+
+    assert!(Share::parse("Wordle 12 2/6\n🟩🟩🟩🟩🟩").is_err());
+    assert!(Share::parse("Wordle 12 X/6\n🟩🟩🟩🟩🟩").is_err());
+    assert!(Share::parse("hello\n🟩🟩🟩🟩🟩").is_err());
+}
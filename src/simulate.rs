@@ -0,0 +1,160 @@
+use crate::bench::{self, FeedbackMatrix, SOLVED};
+use crate::dictionary::Alphabet;
+use crate::strategy::{frequency_score, Frequency, Strategy};
+use crate::{Word, MAX_GUESSES};
+use indicatif::ProgressBar;
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::io::{self, Write};
+
+/// Seeds the random player, so the same words always get the same estimates
+const SEED: u64 = 0x5eed;
+
+/// How many games the random player plays of each answer, since one game says little
+pub const TRIALS: usize = 5;
+
+/// An imperfect way of playing, for estimating how hard each answer is for people
+/// rather than for the solver
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerModel {
+    /// Always guesses a candidate that looks like a common word, without adapting
+    /// to which letters the remaining candidates actually share
+    Greedy,
+    /// Guesses any candidate at random
+    Random,
+    /// Guesses like the frequency strategy
+    Frequency,
+}
+
+impl PlayerModel {
+    pub const ALL: [PlayerModel; 3] = [
+        PlayerModel::Greedy,
+        PlayerModel::Random,
+        PlayerModel::Frequency,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlayerModel::Greedy => "greedy",
+            PlayerModel::Random => "random",
+            PlayerModel::Frequency => "frequency",
+        }
+    }
+
+    /// The mean number of guesses the player takes to find each answer, in the
+    /// matrix's order. Running out of guesses counts as one more than allowed.
+    pub fn simulate(
+        &self,
+        matrix: &FeedbackMatrix,
+        alphabet: &Alphabet,
+        progress: &ProgressBar,
+    ) -> Vec<f64> {
+        let score = |guesses: usize| guesses.min(MAX_GUESSES + 1) as f64;
+        let strategy: &dyn Strategy = match self {
+            PlayerModel::Greedy => &Greedy,
+            PlayerModel::Frequency => &Frequency,
+            PlayerModel::Random => {
+                let mut rng = StdRng::seed_from_u64(SEED);
+                return (0..matrix.answers().len())
+                    .map(|answer| {
+                        let total: f64 = (0..TRIALS)
+                            .map(|_| score(play_randomly(matrix, answer, &mut rng)))
+                            .sum();
+                        progress.inc(1);
+                        total / TRIALS as f64
+                    })
+                    .collect();
+            }
+        };
+        bench::bench(matrix, alphabet, strategy, None, progress)
+            .games
+            .iter()
+            .map(|game| score(game.guesses.len()))
+            .collect()
+    }
+}
+
+/// How hard each answer is for people, in the matrix's order, as the mean number of
+/// guesses the player models take
+pub fn human_difficulty(matrix: &FeedbackMatrix, alphabet: &Alphabet) -> Vec<f64> {
+    let mut totals = vec![0.0; matrix.answers().len()];
+    for model in PlayerModel::ALL {
+        let guesses = model.simulate(matrix, alphabet, &ProgressBar::hidden());
+        totals.iter_mut().zip(guesses).for_each(|(t, g)| *t += g);
+    }
+    totals
+        .into_iter()
+        .map(|total| total / PlayerModel::ALL.len() as f64)
+        .collect()
+}
+
+/// Write one row per answer of each player model's mean guesses, in the order of
+/// `PlayerModel::ALL`, and the overall difficulty
+pub fn write_csv(
+    mut out: impl Write,
+    answers: &[Word],
+    results: &[Vec<f64>],
+    difficulty: &[f64],
+) -> io::Result<()> {
+    let models = PlayerModel::ALL.iter().map(|m| m.name()).join(",");
+    writeln!(out, "answer,{},difficulty", models)?;
+    for (i, answer) in answers.iter().enumerate() {
+        let guesses = results.iter().map(|g| format!("{:.2}", g[i])).join(",");
+        writeln!(out, "{},{},{:.3}", answer, guesses, difficulty[i])?;
+    }
+    out.flush()
+}
+
+/// Guesses the candidate whose letters are most common across every answer
+struct Greedy;
+
+impl Strategy for Greedy {
+    fn name(&self) -> &str {
+        "greedy"
+    }
+
+    fn description(&self) -> &str {
+        "Guess the candidate whose letters are most common among all the answers"
+    }
+
+    fn choose(&self, matrix: &FeedbackMatrix, alphabet: &Alphabet, candidates: &[usize]) -> usize {
+        let freq = matrix.answer_frequencies(alphabet);
+        let score = |&c: &usize| frequency_score(&matrix.guesses()[c], alphabet, &freq.letters);
+        // Ties go to the earliest word
+        *candidates.iter().rev().max_by_key(|c| score(c)).unwrap()
+    }
+}
+
+/// Play one answer guessing random candidates, returning how many guesses it took
+fn play_randomly(matrix: &FeedbackMatrix, answer: usize, rng: &mut impl Rng) -> usize {
+    let mut candidates: Vec<usize> = (0..matrix.answers().len()).collect();
+    let mut guesses = 0;
+    loop {
+        let guess = candidates[rng.gen_range(0..candidates.len())];
+        guesses += 1;
+        let pattern = matrix.pattern(guess, answer);
+        if pattern == SOLVED {
+            return guesses;
+        }
+        candidates.retain(|&c| matrix.pattern(guess, c) == pattern);
+    }
+}
+
+#[test]
+fn test_simulate() {
+    use crate::bench::test_matrix;
+    let matrix = test_matrix();
+    let words = matrix.answers();
+    let alphabet = Alphabet::letters();
+    for model in PlayerModel::ALL {
+        let guesses = model.simulate(&matrix, &alphabet, &ProgressBar::hidden());
+        assert_eq!(guesses.len(), words.len());
+        assert!(guesses
+            .iter()
+            .all(|&g| (1.0..=words.len() as f64).contains(&g)));
+    }
+    // Guessing the answer straight away is the only way to take one guess
+    let greedy = PlayerModel::Greedy.simulate(&matrix, &alphabet, &ProgressBar::hidden());
+    assert_eq!(greedy.iter().filter(|&&g| g == 1.0).count(), 1);
+}
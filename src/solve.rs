@@ -0,0 +1,1898 @@
+use crate::dictionary::Alphabet;
+use crate::events::Events;
+use crate::reverse::{format_row, parse_row};
+use crate::stats::SessionRecord;
+use crate::strategy;
+use crate::{
+    display_keys, guess_word, peaks_word, GuessLetter, GuessResult, Tile, Word, WordleError,
+    MAX_GUESSES,
+};
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use rayon::prelude::*;
+use rustyline::error::ReadlineError;
+use std::collections::{BTreeMap, HashMap};
+
+/// The most words listed for each feedback pattern by `partition`
+const PARTITION_WORDS: usize = 12;
+
+/// The most candidates to show the chance of each being the answer for
+const CHANCE_CANDIDATES: usize = 20;
+
+/// The most candidates worth suggesting a probe for. With more, it's usually
+/// better to keep narrowing down with the regular suggestions.
+const PROBE_CANDIDATES: usize = 15;
+
+/// How many results in a row have to leave the most candidates possible before
+/// the host is taken to be adversarial. One could easily be chance.
+const ADVERSARIAL_RESULTS: usize = 2;
+
+/// How many suggestions `--diverse` shows
+const DIVERSE_SUGGESTIONS: usize = 10;
+
+/// How many letters a suggestion can share with a better one before `--diverse`
+/// counts it as too similar
+const SIMILAR_LETTERS: usize = 4;
+
+/// The most guesses `plan` scripts, since a game has no more than that anyway
+const PLAN_ROUNDS: usize = MAX_GUESSES;
+
+/// The width assumed when the output isn't a terminal
+const DEFAULT_WIDTH: usize = 80;
+
+/// The gap after each column of suggestions
+const COLUMN_GAP: usize = 2;
+
+/// Everything learned about the answer from the results entered so far
+#[derive(Debug, Default)]
+struct Constraints {
+    omit_letters: Vec<char>,
+    req_letters: Vec<char>,
+    /// Letters the answer contains an exact number of copies of
+    exact_letters: Vec<(char, usize)>,
+    cand_letters: Vec<FoundLetter>,
+}
+
+#[derive(Debug)]
+struct FoundLetter {
+    letter: char,
+    position: usize,
+    correct_location: bool,
+}
+
+impl Constraints {
+    /// Parse one guess's result
+    fn parse(line: &str, alphabet: &Alphabet) -> Result<Self, WordleError> {
+        let invalid = || WordleError::InvalidResult(line.trim().to_string());
+        let mut constraints = Constraints::default();
+        let mut position = 0;
+        let mut negate_next = false;
+        let mut correct_next = false;
+        let mut grayed = vec![];
+        for c in line.chars() {
+            match c {
+                c if alphabet.contains(c) => {
+                    if negate_next {
+                        grayed.push((c, position));
+                        negate_next = false;
+                    } else {
+                        constraints.found(c, position, correct_next);
+                        correct_next = false;
+                    }
+                    position += 1;
+                }
+                c if alphabet.contains(c.to_ascii_lowercase()) => {
+                    constraints.found(c.to_ascii_lowercase(), position, true);
+                    position += 1;
+                }
+                '!' | '`' | '\'' => negate_next = true,
+                // A placeholder for a position there's nothing to record about
+                '?' | '.' if !negate_next && !correct_next => position += 1,
+                // Symbols without an uppercase form use a prefix to mark the correct location
+                '=' => correct_next = true,
+                _ => return Err(invalid()),
+            }
+        }
+        if position > 5 {
+            return Err(invalid());
+        }
+        // A gray tile for a letter that's yellow or green elsewhere in the
+        // same guess means the answer has no more copies than were found
+        for (c, position) in grayed {
+            let found = constraints
+                .cand_letters
+                .iter()
+                .filter(|cand| cand.letter == c)
+                .count();
+            if found == 0 {
+                constraints.omit_letters.push(c);
+            } else {
+                constraints.exact_letters.push((c, found));
+                constraints.cand_letters.push(FoundLetter {
+                    letter: c,
+                    position,
+                    correct_location: false,
+                });
+            }
+        }
+        Ok(constraints)
+    }
+
+    /// Why this result can't be true of the same answer as an earlier one, if it can't
+    fn contradiction(&self, earlier: &Constraints) -> Option<String> {
+        let absent = |a: &Constraints, b: &Constraints| {
+            a.req_letters
+                .iter()
+                .find(|c| b.omit_letters.contains(c))
+                .map(|c| format!("'{}' was absent", c.to_ascii_uppercase()))
+        };
+        let green = |c: &Constraints, position: usize| {
+            c.cand_letters
+                .iter()
+                .find(|cand| cand.correct_location && cand.position == position)
+                .map(|cand| cand.letter)
+        };
+        absent(self, earlier)
+            .or_else(|| absent(earlier, self))
+            .or_else(|| {
+                (0..5).find_map(|position| {
+                    match (green(self, position), green(earlier, position)) {
+                        (Some(a), Some(b)) if a != b => Some(format!(
+                            "position {} can't be both '{}' and '{}'",
+                            position + 1,
+                            b.to_ascii_uppercase(),
+                            a.to_ascii_uppercase()
+                        )),
+                        _ => None,
+                    }
+                })
+            })
+    }
+
+    fn found(&mut self, letter: char, position: usize, correct_location: bool) {
+        self.req_letters.push(letter);
+        self.cand_letters.push(FoundLetter {
+            letter,
+            position,
+            correct_location,
+        });
+    }
+
+    /// The answer, if every letter was in the correct location
+    fn answer(&self) -> Option<Word> {
+        let mut answer = Word::default();
+        let mut found = [false; 5];
+        for cand in self.cand_letters.iter().filter(|c| c.correct_location) {
+            answer.0[cand.position] = cand.letter;
+            found[cand.position] = true;
+        }
+        found.iter().all(|&f| f).then_some(answer)
+    }
+
+    fn len(&self) -> usize {
+        self.omit_letters.len()
+            + self.req_letters.len()
+            + self.exact_letters.len()
+            + self.cand_letters.len()
+    }
+
+    /// Each constraint in words, in the order `remove` counts them
+    fn descriptions(&self) -> Vec<String> {
+        let omit = self.omit_letters.iter().map(|c| format!("no '{}'", c));
+        let req = self.req_letters.iter().map(|c| format!("contains '{}'", c));
+        let exact = self
+            .exact_letters
+            .iter()
+            .map(|(c, count)| format!("exactly {} '{}'", count, c));
+        let cand = self.cand_letters.iter().map(|cand| {
+            let not = if cand.correct_location { "" } else { "not " };
+            format!("'{}' {}in position {}", cand.letter, not, cand.position + 1)
+        });
+        omit.chain(req).chain(exact).chain(cand).collect()
+    }
+
+    fn remove(&mut self, mut index: usize) {
+        if index < self.omit_letters.len() {
+            self.omit_letters.remove(index);
+            return;
+        }
+        index -= self.omit_letters.len();
+        if index < self.req_letters.len() {
+            self.req_letters.remove(index);
+            return;
+        }
+        index -= self.req_letters.len();
+        if index < self.exact_letters.len() {
+            self.exact_letters.remove(index);
+            return;
+        }
+        index -= self.exact_letters.len();
+        self.cand_letters.remove(index);
+    }
+}
+
+/// The results entered so far, each with what it says about the answer
+type Entries = Vec<(String, Constraints)>;
+
+/// What happened in a solve session, for a summary at the end
+struct Session {
+    variant: &'static str,
+    /// The candidates each time suggestions were shown, best suggestion first
+    rounds: Vec<Vec<Word>>,
+}
+
+impl Session {
+    fn new(variant: &'static str) -> Self {
+        Session {
+            variant,
+            rounds: vec![],
+        }
+    }
+
+    fn round(&mut self, scores: &BTreeMap<i32, Vec<Word>>) {
+        self.rounds
+            .push(scores.values().flatten().copied().collect());
+    }
+
+    /// Show the summary of the results still standing, and return the session for the
+    /// profile's history. Without a result saying what the answer was, it's only known
+    /// if there was one candidate left.
+    fn finish(
+        self,
+        results: Vec<String>,
+        answer: Option<Word>,
+        events: &mut Events,
+    ) -> Result<SessionRecord> {
+        let guesses = answer.map(|_| results.len());
+        let answer = answer.or_else(|| match self.rounds.last()?[..] {
+            [word] => Some(word),
+            _ => None,
+        });
+        println!();
+        println!("Results entered: {}", results.iter().join(", "));
+        println!(
+            "Candidates: {}",
+            self.rounds.iter().map(|round| round.len()).join(" → ")
+        );
+        if let Some(answer) = answer {
+            let ranks = self.rounds.iter().map(|round| {
+                match round.iter().position(|&word| word == answer) {
+                    Some(rank) => format!("#{}", rank + 1),
+                    None => "-".to_string(),
+                }
+            });
+            println!(
+                "The answer, \"{}\", was suggestion {}",
+                answer,
+                ranks.format(" → ")
+            );
+        }
+        events.game_over(answer, guesses)?;
+        let candidates = self.rounds.iter().map(|round| round.len()).collect();
+        Ok(SessionRecord::new(
+            self.variant,
+            results,
+            candidates,
+            answer,
+        ))
+    }
+}
+
+/// How likely each word is to be the answer, before any results are known
+struct Prior<'a> {
+    alphabet: &'a Alphabet,
+    /// Letter frequencies to weight words by, or `None` for all words being equally likely
+    freq: Option<Vec<i32>>,
+}
+
+impl<'a> Prior<'a> {
+    /// Weighting favors words spelled with common letters, which tend to be common words
+    fn new(words: &[Word], alphabet: &'a Alphabet, weighted: bool) -> Self {
+        Prior {
+            alphabet,
+            freq: weighted.then(|| strategy::letter_frequencies(words, alphabet)),
+        }
+    }
+
+    fn weight(&self, word: &Word) -> f64 {
+        match &self.freq {
+            Some(freq) => strategy::frequency_score(word, self.alphabet, freq) as f64,
+            None => 1.0,
+        }
+    }
+
+    /// The chance of each candidate being the answer, from the most likely
+    fn chances(&self, candidates: &[Word]) -> Vec<(Word, f64)> {
+        let weights: Vec<f64> = candidates.iter().map(|w| self.weight(w)).collect();
+        let total: f64 = weights.iter().sum();
+        let mut chances: Vec<(Word, f64)> = candidates
+            .iter()
+            .zip(weights)
+            .map(|(&word, weight)| (word, weight / total))
+            .collect();
+        chances.sort_by(|a, b| b.1.total_cmp(&a.1));
+        chances
+    }
+
+    /// Once there are few enough candidates to read through, show their chances
+    fn display_chances(&self, candidates: &[Word]) {
+        if !(2..=CHANCE_CANDIDATES).contains(&candidates.len()) {
+            return;
+        }
+        println!("Chance of being the answer:");
+        for (word, chance) in self.chances(candidates) {
+            println!("{:5.1}% {}", chance * 100.0, word);
+        }
+    }
+}
+
+/// A result split into the symbols for each position, such as `!a` or `E`
+fn result_tiles(line: &str) -> Option<Vec<String>> {
+    let mut tiles = vec![];
+    let mut tile = String::new();
+    for c in line.trim().chars() {
+        tile.push(c);
+        if !matches!(c, '!' | '`' | '\'' | '=') {
+            tiles.push(std::mem::take(&mut tile));
+        }
+    }
+    (tile.is_empty() && tiles.len() == 5).then_some(tiles)
+}
+
+/// When no word fits the results, look for a single tile that could have been
+/// misread, such as a yellow taken for a green. Reports every correction that
+/// leaves some candidates, and returns the index and corrected entry of the one
+/// that leaves the most.
+fn correct_mistake(
+    words: &[Word],
+    entries: &Entries,
+    alphabet: &Alphabet,
+) -> Option<(usize, (String, Constraints))> {
+    const COLORS: [(&str, &str); 3] = [("!", "gray"), ("", "yellow"), ("=", "green")];
+    let mut corrections = vec![];
+    for (index, (line, _)) in entries.iter().enumerate() {
+        let tiles = match result_tiles(line) {
+            Some(tiles) => tiles,
+            None => continue,
+        };
+        for (position, tile) in tiles.iter().enumerate() {
+            let mut chars = tile.chars();
+            let symbol = match chars.next_back() {
+                Some(c) if alphabet.contains(c.to_ascii_lowercase()) => c,
+                _ => continue,
+            };
+            let letter = symbol.to_ascii_lowercase();
+            let was = match chars.as_str() {
+                "" if symbol != letter => "green",
+                "" => "yellow",
+                "=" => "green",
+                _ => "gray",
+            };
+            for &(new_prefix, color) in COLORS.iter().filter(|&&(_, c)| c != was) {
+                let mut new_tiles = tiles.clone();
+                new_tiles[position] = format!("{}{}", new_prefix, letter);
+                let new_line = new_tiles.concat();
+                let new = match Constraints::parse(&new_line, alphabet) {
+                    Ok(new) => new,
+                    Err(_) => continue,
+                };
+                let left = words
+                    .iter()
+                    .filter(|word| {
+                        is_candidate(word, &new)
+                            && entries
+                                .iter()
+                                .enumerate()
+                                .all(|(i, (_, c))| i == index || is_candidate(word, c))
+                    })
+                    .count();
+                if left > 0 {
+                    let note = format!(
+                        "Result {}: '{}' in position {} as {}, not {}, leaves {} candidates",
+                        index + 1,
+                        letter,
+                        position + 1,
+                        color,
+                        was,
+                        left
+                    );
+                    corrections.push((left, note, index, new_line, new));
+                }
+            }
+        }
+    }
+    if corrections.is_empty() {
+        println!("No word fits the results, even with one tile corrected.");
+        return None;
+    }
+    println!("No word fits the results. One tile may have been entered wrong:");
+    for (_, note, ..) in &corrections {
+        println!("  {}", note);
+    }
+    let (_, note, index, line, new) = corrections
+        .into_iter()
+        .rev()
+        .max_by_key(|(left, ..)| *left)?;
+    println!("Going with {}. Use `retract-guess` to undo it.", note);
+    Some((index, (line, new)))
+}
+
+/// Whether the results so far are what an adversarial host would give, which is
+/// the feedback leaving the most candidates every time. Results with placeholders,
+/// or for guesses against two or fewer candidates, don't tell either way.
+fn is_adversarial(words: &[Word], entries: &Entries, alphabet: &Alphabet) -> bool {
+    let mut candidates = words.to_vec();
+    let mut checked = 0;
+    for (line, constraints) in entries {
+        let remaining: Vec<Word> = candidates
+            .iter()
+            .filter(|word| is_candidate(word, constraints))
+            .copied()
+            .collect();
+        let guess = result_tiles(line).and_then(|tiles| {
+            let letters: String = tiles
+                .iter()
+                .filter_map(|tile| tile.chars().last())
+                .map(|c| c.to_ascii_lowercase())
+                .collect();
+            // Placeholders aren't in the alphabet, so they leave no guess to check
+            alphabet.check_word(&letters).ok()
+        });
+        if let (Some(guess), true) = (guess, candidates.len() > 2) {
+            let largest = candidates
+                .iter()
+                .counts_by(|&c| guess_word(guess, c).pattern())
+                .into_values()
+                .max()
+                .unwrap_or(0);
+            if remaining.len() < largest {
+                return false;
+            }
+            checked += 1;
+        }
+        candidates = remaining;
+    }
+    checked >= ADVERSARIAL_RESULTS
+}
+
+/// Rank the candidates by the most candidates any feedback to them could leave,
+/// smallest first, for when the host picks the feedback that's worst for us. The
+/// size is the key, so the score shown is minus the size.
+fn minimax_scores(candidates: &[Word]) -> BTreeMap<i32, Vec<Word>> {
+    let mut scores: BTreeMap<i32, Vec<Word>> = BTreeMap::new();
+    for &guess in candidates {
+        let worst = candidates
+            .iter()
+            .filter(|&&c| c != guess)
+            .counts_by(|&c| guess_word(guess, c).pattern())
+            .into_values()
+            .max()
+            .unwrap_or(0);
+        scores.entry(worst as i32).or_default().push(guess);
+    }
+    scores
+}
+
+/// Read the next line in the solve loop, or `None` once the user quits
+fn read_result(rl: &mut rustyline::Editor<()>, prompt: &str) -> Result<Option<String>> {
+    match rl.readline(prompt) {
+        Ok(line) => Ok(Some(line)),
+        Err(ReadlineError::Eof | ReadlineError::Interrupted) => Ok(None),
+        Err(e) => Err(anyhow!("Error: {}", e)),
+    }
+}
+
+/// Which of the solver's optional behaviors are turned on
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// Weight the chances of the last few candidates by how common their letters are
+    pub weighted: bool,
+    /// Correct a tile that was entered wrong once no word fits the results
+    pub fuzzy: bool,
+    /// Suggest for the worst case once the host looks adversarial
+    pub evil: bool,
+    /// Keep the suggestions shown from being near-anagrams of each other
+    pub diverse: bool,
+}
+
+/// Suggest guesses from the words, also searching every accepted word for probes.
+/// `counts` are how often each letter appears in the words.
+pub fn solve(
+    words: Vec<Word>,
+    counts: &[i32],
+    accepted: &[Word],
+    alphabet: &Alphabet,
+    options: Options,
+    events: &mut Events,
+) -> Result<SessionRecord> {
+    let Options {
+        weighted,
+        fuzzy,
+        evil,
+        diverse,
+    } = options;
+    let prior = Prior::new(&words, alphabet, weighted);
+    let mut entries: Entries = vec![];
+    let mut candidates = words.clone();
+    let mut session = Session::new("wordle");
+    let mut rl = rustyline::Editor::<()>::new();
+    let mut adversarial = false;
+    loop {
+        // Before any results, or once they're dropped, every word is a candidate again
+        let known = (candidates.len() == words.len()).then_some(counts);
+        let (new_words, mut scores) = suggest(&candidates, alphabet, known, |word| {
+            entries.iter().all(|(_, c)| is_candidate(word, c))
+        });
+        if fuzzy && new_words.is_empty() {
+            if let Some((index, entry)) = correct_mistake(&words, &entries, alphabet) {
+                entries[index] = entry;
+                candidates = words.clone();
+                continue;
+            }
+        }
+        if evil {
+            let was_adversarial = adversarial;
+            adversarial = is_adversarial(&words, &entries, alphabet);
+            if adversarial && !was_adversarial {
+                println!("The host looks adversarial, giving whichever feedback leaves the most candidates. Suggesting for the worst case from now on.");
+            } else if was_adversarial && !adversarial {
+                println!("The host no longer looks adversarial. Back to the usual suggestions.");
+            }
+            if adversarial {
+                scores = minimax_scores(&new_words);
+            }
+        }
+        session.round(&scores);
+        // Only the suggestions shown change, not the candidates
+        let shown = match diverse {
+            true => diversify(&scores),
+            false => scores.clone(),
+        };
+        let coverage = Coverage::new(&entries);
+        display_suggestions(&shown, Some(&coverage));
+        display_pace(&scores, entries.len(), guess_word);
+        display_keys(alphabet, &coverage.keys(alphabet));
+        prior.display_chances(&new_words);
+        events.suggestions(&top_suggestions(&shown), new_words.len())?;
+        if let Some((probe, letters)) = find_probe(&new_words, accepted) {
+            println!(
+                "The candidates only differ in one position. Guessing \"{}\" tests {} at once.",
+                probe,
+                letters.iter().join(", ")
+            );
+        }
+        candidates = new_words;
+        // The suggestion picked with `use`, so only its feedback needs entering
+        let mut played = None;
+        loop {
+            let prompt = match played {
+                Some(word) => format!("Feedback for \"{}\": ", word),
+                None => "Result: ".to_string(),
+            };
+            let mut line = match read_result(&mut rl, &prompt)? {
+                Some(line) => line,
+                None => return session.finish(entry_lines(&entries), None, events),
+            };
+            // Why the line isn't the feedback for the word picked with `use`, if it isn't
+            let mut not_feedback = None;
+            if let Some(word) = played {
+                match pasted_feedback(&line)
+                    .ok_or_else(|| {
+                        anyhow!("Enter the feedback as g, y and b (or .), e.g. \"gyb.b\".")
+                    })
+                    .and_then(|feedback| spell_result(word, &feedback, wordle_feedback))
+                {
+                    Ok(result) => line = result,
+                    Err(e) => not_feedback = Some(e),
+                }
+            }
+            if let Some(result) = use_command(&line, &shown) {
+                match result {
+                    Ok(word) => {
+                        played = Some(word);
+                        println!("Enter the feedback as g, y and b (or .), e.g. \"gyb.b\".");
+                    }
+                    Err(e) => eprintln!("{}", e),
+                }
+                continue;
+            }
+            if partition_command(&line, &candidates, alphabet, guess_word) {
+                continue;
+            }
+            if let Some(result) = whatif_command(&line, alphabet, wordle_feedback) {
+                // Only the current candidates need checking against the new result
+                match result.and_then(|line| Ok(Constraints::parse(&line, alphabet)?)) {
+                    Ok(new) => {
+                        display_whatif(&candidates, alphabet, |word| is_candidate(word, &new))
+                    }
+                    Err(e) => eprintln!("{}", e),
+                }
+                continue;
+            }
+            if command_args(&line, "constraints").is_some() {
+                display_constraints(&entries);
+                continue;
+            }
+            if command_args(&line, "board").is_some() {
+                display_positions(&entries);
+                continue;
+            }
+            if command_args(&line, "entropy").is_some() {
+                display_position_entropy(&candidates);
+                continue;
+            }
+            if plan_command(&line, &candidates, accepted, &coverage) {
+                continue;
+            }
+            if let Some(result) =
+                drop_command(&line, &mut entries).or_else(|| retract_command(&line, &mut entries))
+            {
+                match result {
+                    // Without the constraint, words that were ruled out may be back
+                    Ok(()) => {
+                        candidates = words.clone();
+                        break;
+                    }
+                    Err(e) => eprintln!("{}", e),
+                }
+                continue;
+            }
+            // A mistyped color mustn't be read as hints about the letters typed
+            if let Some(e) = not_feedback {
+                eprintln!("{}", e);
+                continue;
+            }
+            if let Some(result) = pasted_result(&line, alphabet) {
+                line = result;
+            }
+            // Fuzzy solving corrects contradictions instead, once no candidates are left
+            let new = Constraints::parse(&line, alphabet).and_then(|new| match fuzzy {
+                true => Ok(new),
+                false => check_consistent(&entries, &line, new),
+            });
+            match new {
+                Ok(new) => {
+                    let answer = new.answer();
+                    entries.push((line.trim().to_string(), new));
+                    if let Some(guess) = result_guess(&line, alphabet) {
+                        events.guess_submitted(entries.len(), guess)?;
+                    }
+                    events.result_entered(entries.len(), line.trim())?;
+                    if answer.is_some() {
+                        return session.finish(entry_lines(&entries), answer, events);
+                    }
+                    break;
+                }
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+    }
+}
+
+/// The results still standing, as they were entered or corrected
+fn entry_lines(entries: &Entries) -> Vec<String> {
+    entries.iter().map(|(line, _)| line.clone()).collect()
+}
+
+/// The new result, unless it contradicts one of the earlier ones
+fn check_consistent(
+    entries: &Entries,
+    line: &str,
+    new: Constraints,
+) -> Result<Constraints, WordleError> {
+    for (earlier, constraints) in entries {
+        if let Some(reason) = new.contradiction(constraints) {
+            return Err(WordleError::ContradictoryHint {
+                line: line.trim().to_string(),
+                earlier: earlier.clone(),
+                reason,
+            });
+        }
+    }
+    Ok(new)
+}
+
+pub fn solve_peaks(
+    mut words: Vec<Word>,
+    counts: &[i32],
+    alphabet: &Alphabet,
+    weighted: bool,
+    events: &mut Events,
+) -> Result<SessionRecord> {
+    let prior = Prior::new(&words, alphabet, weighted);
+    // The half-open range of alphabet indices still possible in each position
+    let mut ranges = [(0, alphabet.len()); 5];
+    let mut session = Session::new("peaks");
+    let mut results = vec![];
+    let mut rl = rustyline::Editor::<()>::new();
+    loop {
+        let known = results.is_empty().then_some(counts);
+        let (new_words, scores) = suggest(&words, alphabet, known, |word| {
+            is_peaks_candidate(word, alphabet, &ranges)
+        });
+        session.round(&scores);
+        display_suggestions(&scores, None);
+        display_pace(&scores, results.len(), peaks_word);
+        prior.display_chances(&new_words);
+        events.suggestions(&top_suggestions(&scores), new_words.len())?;
+        words = new_words;
+        loop {
+            let line = match read_result(&mut rl, "Result: ")? {
+                Some(line) => line,
+                None => return session.finish(results, None, events),
+            };
+            if partition_command(&line, &words, alphabet, peaks_word) {
+                continue;
+            }
+            if let Some(result) = whatif_command(&line, alphabet, peaks_feedback) {
+                match result.map(|line| parse_peaks(&line, alphabet, ranges)) {
+                    Ok(Some(ranges)) => display_whatif(&words, alphabet, |word| {
+                        is_peaks_candidate(word, alphabet, &ranges)
+                    }),
+                    Ok(None) => eprintln!("Invalid entry"),
+                    Err(e) => eprintln!("{}", e),
+                }
+                continue;
+            }
+            match parse_peaks(&line, alphabet, ranges) {
+                Some(new_ranges) => {
+                    ranges = new_ranges;
+                    results.push(line.trim().to_string());
+                    if let Some(guess) = result_guess(&line, alphabet) {
+                        events.guess_submitted(results.len(), guess)?;
+                    }
+                    events.result_entered(results.len(), line.trim())?;
+                    if ranges.iter().all(|(lo, hi)| lo + 1 == *hi) {
+                        let mut answer = Word::default();
+                        for (c, &(lo, _)) in answer.0.iter_mut().zip(&ranges) {
+                            *c = alphabet.symbols()[lo];
+                        }
+                        return session.finish(results, Some(answer), events);
+                    }
+                    break;
+                }
+                None => eprintln!("Invalid entry"),
+            }
+        }
+    }
+}
+
+/// The word a result was for, unless it leaves some of the letters out
+fn result_guess(line: &str, alphabet: &Alphabet) -> Option<Word> {
+    let letters: String = line
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, '!' | '`' | '\'' | '=' | '+' | '-'))
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    alphabet.check_word(&letters).ok()
+}
+
+/// Narrow the ranges with one guess's result, or `None` if the line isn't a valid result
+fn parse_peaks(
+    line: &str,
+    alphabet: &Alphabet,
+    mut ranges: [(usize, usize); 5],
+) -> Option<[(usize, usize); 5]> {
+    let mut position = 0;
+    let mut direction = None;
+    for c in line.chars() {
+        let index = alphabet.index(c.to_ascii_lowercase());
+        match (index, direction) {
+            (Some(index), Some(dir)) if position < 5 && !c.is_uppercase() => {
+                let (lo, hi) = &mut ranges[position];
+                match dir {
+                    '+' => *lo = (*lo).max(index + 1),
+                    '-' => *hi = (*hi).min(index),
+                    _ => (*lo, *hi) = (index, index + 1),
+                }
+                direction = None;
+                position += 1;
+            }
+            (Some(index), None) if position < 5 && c.is_uppercase() => {
+                ranges[position] = (index, index + 1);
+                position += 1;
+            }
+            (_, None) if matches!(c, '+' | '-' | '=') => direction = Some(c),
+            (_, None) if matches!(c, '?' | '.') && position < 5 => position += 1,
+            _ => return None,
+        }
+    }
+    if position != 5 || direction.is_some() {
+        return None;
+    }
+    Some(ranges)
+}
+
+/// When a handful of candidates differ in a single position, guessing them one
+/// at a time can take a guess each. Find the accepted word containing the most
+/// of the letters that tell them apart, along with the letters it contains.
+fn find_probe(candidates: &[Word], accepted: &[Word]) -> Option<(Word, Vec<char>)> {
+    if !(2..=PROBE_CANDIDATES).contains(&candidates.len()) {
+        return None;
+    }
+    let (position,) = (0..5)
+        .filter(|&pos| candidates.iter().map(|w| w.0[pos]).unique().count() > 1)
+        .collect_tuple()?;
+    let letters: Vec<char> = candidates.iter().map(|w| w.0[position]).unique().collect();
+
+    let tested = |word: &Word| -> Vec<char> {
+        letters
+            .iter()
+            .copied()
+            .filter(|c| word.0.contains(c))
+            .collect()
+    };
+    // Ties go to the earliest word, so answers are preferred with more words enabled
+    let (probe, tested) = accepted
+        .iter()
+        .rev()
+        .map(|&word| (word, tested(&word)))
+        .max_by_key(|(_, tested)| tested.len())?;
+    // Testing one letter is no better than guessing a candidate
+    (tested.len() > 1).then_some((probe, tested))
+}
+
+/// Script `rounds` guesses to play one after another without looking at their
+/// feedback, each testing the most of what the earlier ones leave untested. A
+/// letter is worth the number of candidates containing it, so the plan covers the
+/// letters that matter, and letters already known to be in or out count for nothing.
+/// Returns each guess with the letters it's the first to test.
+fn plan(
+    candidates: &[Word],
+    accepted: &[Word],
+    known: &[char],
+    rounds: usize,
+) -> Vec<(Word, Vec<char>)> {
+    let mut worth: HashMap<char, usize> = HashMap::new();
+    for word in candidates {
+        for c in word.0.iter().unique() {
+            if !known.contains(c) {
+                *worth.entry(*c).or_default() += 1;
+            }
+        }
+    }
+    let mut script: Vec<(Word, Vec<char>)> = vec![];
+    for _ in 0..rounds {
+        let untested = |word: &Word| -> Vec<char> {
+            word.0
+                .iter()
+                .unique()
+                .filter(|c| worth.contains_key(c))
+                .copied()
+                .collect()
+        };
+        let value = |word: &Word| -> usize { untested(word).iter().map(|c| worth[c]).sum() };
+        // The first of the best, so the plan doesn't depend on the order ties are seen in
+        let best = accepted
+            .iter()
+            .rev()
+            .max_by_key(|word| value(word))
+            .filter(|word| value(word) > 0);
+        let Some(&guess) = best else {
+            break;
+        };
+        let letters = untested(&guess);
+        for c in &letters {
+            worth.remove(c);
+        }
+        script.push((guess, letters));
+    }
+    script
+}
+
+/// Handle `plan K` (or `plan --rounds K`), showing the K guesses that test the
+/// most letters between them. Returns whether the line was a plan command.
+fn plan_command(line: &str, candidates: &[Word], accepted: &[Word], coverage: &Coverage) -> bool {
+    let args = match command_args(line, "plan") {
+        Some(args) => args,
+        None => return false,
+    };
+    let rounds = match args[..] {
+        [k] | ["--rounds", k] => k.parse::<usize>().ok(),
+        _ => None,
+    };
+    let rounds = match rounds {
+        Some(k) if (1..=PLAN_ROUNDS).contains(&k) => k,
+        _ => {
+            eprintln!("Usage: plan K (from 1 to {})", PLAN_ROUNDS);
+            return true;
+        }
+    };
+    let known: Vec<char> = coverage
+        .absent
+        .iter()
+        .chain(&coverage.present)
+        .copied()
+        .collect();
+    let script = plan(candidates, accepted, &known, rounds);
+    if script.is_empty() {
+        println!("Every letter in the candidates has already been tested.");
+    }
+    for (i, (guess, letters)) in script.iter().enumerate() {
+        println!("{}. {}  tests {}", i + 1, guess, letters.iter().join(", "));
+    }
+    if script.len() < rounds {
+        println!("That tests every letter left, so the rest of the guesses are free.");
+    }
+    true
+}
+
+/// The arguments of a command typed in place of a result, or `None` if the line isn't that command
+fn command_args<'a>(line: &'a str, command: &str) -> Option<Vec<&'a str>> {
+    let mut args = line.split_whitespace();
+    (args.next() == Some(command)).then(|| args.collect())
+}
+
+/// Handle `partition WORD`, returning whether the line was a partition command
+fn partition_command<L: Tile>(
+    line: &str,
+    candidates: &[Word],
+    alphabet: &Alphabet,
+    feedback: fn(Word, Word) -> GuessResult<L>,
+) -> bool {
+    let args = match command_args(line, "partition") {
+        Some(args) => args,
+        None => return false,
+    };
+    match args[..] {
+        [word] => match alphabet.parse_word(word) {
+            Ok(guess) => display_partition(guess, candidates, feedback),
+            Err(e) => eprintln!("{}", e),
+        },
+        _ => eprintln!("Usage: partition WORD"),
+    }
+    true
+}
+
+/// List every constraint, numbered for `drop`, under the result it came from,
+/// numbered for `retract-guess`
+fn display_constraints(entries: &Entries) {
+    if entries.is_empty() {
+        println!("No constraints yet.");
+        return;
+    }
+    let mut number = 1;
+    for (i, (line, constraints)) in entries.iter().enumerate() {
+        println!("Result {}: {}", i + 1, line);
+        for description in constraints.descriptions() {
+            println!("{:4}. {}", number, description);
+            number += 1;
+        }
+    }
+}
+
+/// What the results say about each position: the letter found there, if any, and
+/// the letters found elsewhere that can't be there
+fn position_summary(entries: &Entries) -> [(Option<char>, Vec<char>); 5] {
+    let mut positions: [(Option<char>, Vec<char>); 5] = Default::default();
+    for cand in entries.iter().flat_map(|(_, c)| &c.cand_letters) {
+        let (correct, excluded) = &mut positions[cand.position];
+        if cand.correct_location {
+            *correct = Some(cand.letter);
+        } else if !excluded.contains(&cand.letter) {
+            excluded.push(cand.letter);
+        }
+    }
+    for (_, excluded) in &mut positions {
+        excluded.sort_unstable();
+    }
+    positions
+}
+
+/// Handle `board`, showing a line per position of what's known about it
+fn display_positions(entries: &Entries) {
+    for (i, (correct, excluded)) in position_summary(entries).iter().enumerate() {
+        let letter = correct.map_or('?', |c| c.to_ascii_uppercase());
+        if excluded.is_empty() || correct.is_some() {
+            println!("{}: {}", i + 1, letter);
+        } else {
+            println!("{}: {}  not {}", i + 1, letter, excluded.iter().join(", "));
+        }
+    }
+}
+
+/// How uncertain each position is over the candidates: the letters it could
+/// still be, most likely first, and the entropy of the letter there in bits
+fn position_entropy(candidates: &[Word]) -> [(Vec<char>, f64); 5] {
+    let mut positions: [(Vec<char>, f64); 5] = Default::default();
+    for (i, (letters, entropy)) in positions.iter_mut().enumerate() {
+        let counts = candidates.iter().map(|w| w.0[i]).counts();
+        let total = candidates.len() as f64;
+        *entropy = counts
+            .values()
+            .map(|&n| {
+                let p = n as f64 / total;
+                p * (1.0 / p).log2()
+            })
+            .sum();
+        *letters = counts
+            .into_iter()
+            .sorted_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)))
+            .map(|(c, _)| c)
+            .collect();
+    }
+    positions
+}
+
+/// Handle `entropy`, showing a bar per position for how uncertain it still is,
+/// so the next probe can target the most uncertain ones
+fn display_position_entropy(candidates: &[Word]) {
+    if candidates.is_empty() {
+        println!("No candidates are left.");
+        return;
+    }
+    for (i, (letters, entropy)) in position_entropy(candidates).iter().enumerate() {
+        // A bar per half bit, so a position that could be any of 26 letters is about 9 long
+        let bar = "#".repeat((entropy * 2.0).round() as usize);
+        let count = match letters.len() {
+            1 => "1 letter".to_string(),
+            n => format!("{} letters", n),
+        };
+        println!(
+            "{}: {:<10} {:.2} bits, {} ({})",
+            i + 1,
+            bar,
+            entropy,
+            count,
+            letters.iter().take(PARTITION_WORDS).join("")
+        );
+    }
+}
+
+/// Handle `drop N`, removing the constraint numbered N by `constraints`. Returns
+/// `None` if the line isn't a drop command.
+fn drop_command(line: &str, entries: &mut Entries) -> Option<Result<()>> {
+    let args = command_args(line, "drop")?;
+    let mut index = match args[..] {
+        [n] => match n.parse::<usize>() {
+            Ok(n) if n > 0 => n - 1,
+            _ => return Some(Err(anyhow!("Usage: drop N"))),
+        },
+        _ => return Some(Err(anyhow!("Usage: drop N"))),
+    };
+    for (_, constraints) in entries.iter_mut() {
+        if index < constraints.len() {
+            constraints.remove(index);
+            return Some(Ok(()));
+        }
+        index -= constraints.len();
+    }
+    Some(Err(anyhow!(
+        "There's no constraint {}. See `constraints` for the list.",
+        line.split_whitespace().nth(1).unwrap_or_default()
+    )))
+}
+
+/// Handle `retract-guess N`, removing the Nth result entered and everything it said.
+/// Returns `None` if the line isn't a retract-guess command.
+fn retract_command(line: &str, entries: &mut Entries) -> Option<Result<()>> {
+    let args = command_args(line, "retract-guess")?;
+    let n = match args[..] {
+        [n] => n.parse::<usize>().ok(),
+        _ => None,
+    };
+    match n {
+        Some(n) if (1..=entries.len()).contains(&n) => {
+            entries.remove(n - 1);
+            Some(Ok(()))
+        }
+        Some(n) => Some(Err(anyhow!(
+            "There's no result {}. See `constraints` for the list.",
+            n
+        ))),
+        None => Some(Err(anyhow!("Usage: retract-guess N"))),
+    }
+}
+
+/// Handle `whatif GUESS FEEDBACK` by spelling out the result line it stands for,
+/// using the prefix each feedback symbol puts before its letter. Returns `None`
+/// if the line isn't a whatif command.
+fn whatif_command(
+    line: &str,
+    alphabet: &Alphabet,
+    prefix: fn(char) -> Option<&'static str>,
+) -> Option<Result<String>> {
+    let args = command_args(line, "whatif")?;
+    let (guess, feedback) = match args[..] {
+        [guess, feedback] => (guess, feedback),
+        _ => return Some(Err(anyhow!("Usage: whatif GUESS FEEDBACK"))),
+    };
+    Some(
+        alphabet
+            .parse_word(guess)
+            .map_err(anyhow::Error::from)
+            .and_then(|guess| spell_result(guess, feedback, prefix)),
+    )
+}
+
+/// Spell out the result line for a guess's feedback, given as one symbol per letter
+fn spell_result(
+    guess: Word,
+    feedback: &str,
+    prefix: fn(char) -> Option<&'static str>,
+) -> Result<String> {
+    if feedback.chars().count() != 5 {
+        return Err(anyhow!("The feedback needs one symbol for each letter"));
+    }
+    guess
+        .0
+        .iter()
+        .zip(feedback.chars())
+        .map(|(&c, f)| {
+            prefix(f)
+                .map(|prefix| format!("{}{}", prefix, c))
+                .ok_or_else(|| anyhow!("Unknown feedback symbol '{}'", f))
+        })
+        .collect()
+}
+
+/// Read a result pasted from notes or chat, e.g. "C r a n e: green, yellow, gray,
+/// gray, gray", as the usual notation. Without a colon, the spaces and commas
+/// are dropped and the letters are read as usual.
+fn pasted_result(line: &str, alphabet: &Alphabet) -> Option<String> {
+    let squeeze = |text: &str| -> String {
+        text.chars()
+            .filter(|&c| !c.is_whitespace() && c != ',')
+            .collect()
+    };
+    match line.split_once(':') {
+        Some((word, feedback)) => {
+            // The colors say which tiles are green, so the case of the word doesn't
+            let word = alphabet.parse_word(&squeeze(word).to_lowercase()).ok()?;
+            spell_result(word, &pasted_feedback(feedback)?, wordle_feedback).ok()
+        }
+        None => Some(squeeze(line)),
+    }
+}
+
+/// Feedback given as a color for each tile (e.g. "green, yellow, gray, gray,
+/// gray"), as colored squares or as g, y and b, written as g, y and b
+fn pasted_feedback(text: &str) -> Option<String> {
+    let tiles: String = text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|tile| !tile.is_empty())
+        .map(|tile| match tile.to_lowercase().as_str() {
+            "green" => "g",
+            "yellow" => "y",
+            "gray" | "grey" | "black" | "white" => "b",
+            _ => tile,
+        })
+        .collect();
+    parse_row(&tiles).ok().map(format_row)
+}
+
+/// Handle `use N` (or `use #N`), picking the suggestion numbered N as the guess
+/// played. Returns `None` if the line isn't a use command.
+fn use_command(line: &str, scores: &BTreeMap<i32, Vec<Word>>) -> Option<Result<Word>> {
+    let args = command_args(line, "use")?;
+    let n = match args[..] {
+        [n] => n.trim_start_matches('#').parse::<usize>().ok(),
+        _ => None,
+    };
+    Some(match n {
+        Some(n) if n > 0 => scores
+            .values()
+            .flatten()
+            .nth(n - 1)
+            .copied()
+            .ok_or_else(|| anyhow!("There's no suggestion #{}", n)),
+        _ => Err(anyhow!("Usage: use N")),
+    })
+}
+
+/// Wordle feedback for whatif: `g` for green, `y` for yellow and `b` or `.` for
+/// gray, or the digits of a pattern code
+fn wordle_feedback(symbol: char) -> Option<&'static str> {
+    match symbol.to_ascii_lowercase() {
+        'g' | '2' => Some("="),
+        'y' | '1' => Some(""),
+        'b' | '.' | '0' => Some("!"),
+        _ => None,
+    }
+}
+
+/// Peaks feedback for whatif, using the same symbols as the results
+fn peaks_feedback(symbol: char) -> Option<&'static str> {
+    match symbol {
+        '+' => Some("+"),
+        '-' => Some("-"),
+        '=' | 'g' | 'G' => Some("="),
+        _ => None,
+    }
+}
+
+/// Show the suggestions as they would be if the candidates were narrowed down
+fn display_whatif(
+    words: &[Word],
+    alphabet: &Alphabet,
+    is_candidate: impl Fn(&Word) -> bool + Sync,
+) {
+    let (words, scores) = suggest(words, alphabet, None, is_candidate);
+    println!("That would leave {} candidates.", words.len());
+    display_suggestions(&scores, None);
+}
+
+/// Show the candidates that would be left after each possible result of the guess,
+/// from the largest group
+pub fn display_partition<L: Tile>(
+    guess: Word,
+    candidates: &[Word],
+    feedback: fn(Word, Word) -> GuessResult<L>,
+) {
+    let mut groups: HashMap<u8, (GuessResult<L>, Vec<Word>)> = HashMap::new();
+    for &candidate in candidates {
+        let result = feedback(guess, candidate);
+        groups
+            .entry(result.pattern())
+            .or_insert_with(|| (result, vec![]))
+            .1
+            .push(candidate);
+    }
+    let groups = groups
+        .into_iter()
+        .sorted_by_key(|(pattern, (_, words))| (std::cmp::Reverse(words.len()), *pattern))
+        .map(|(_, group)| group);
+
+    println!(
+        "\"{}\" splits the {} candidates into {} groups:",
+        guess,
+        candidates.len(),
+        groups.len()
+    );
+    for (result, words) in groups {
+        let mut listed = words.iter().take(PARTITION_WORDS).join(" ");
+        if words.len() > PARTITION_WORDS {
+            listed += &format!(" (+{} more)", words.len() - PARTITION_WORDS);
+        }
+        println!("{} {:5}  {}", result, words.len(), listed);
+    }
+}
+
+fn is_peaks_candidate(word: &Word, alphabet: &Alphabet, ranges: &[(usize, usize); 5]) -> bool {
+    word.0.iter().zip(ranges.iter()).all(|(&c, (lo, hi))| {
+        alphabet
+            .index(c)
+            .is_some_and(|index| (*lo..*hi).contains(&index))
+    })
+}
+
+fn is_candidate(word: &Word, constraints: &Constraints) -> bool {
+    if word.0.iter().any(|c| constraints.omit_letters.contains(c)) {
+        return false;
+    }
+    if !constraints.req_letters.iter().all(|c| word.0.contains(c)) {
+        return false;
+    }
+    if !constraints
+        .exact_letters
+        .iter()
+        .all(|&(c, count)| word.0.iter().filter(|&&l| l == c).count() == count)
+    {
+        return false;
+    }
+    // Now check all the positions
+    for cand in &constraints.cand_letters {
+        if cand.correct_location {
+            if word.0[cand.position] != cand.letter {
+                return false;
+            }
+        } else if word.0[cand.position] == cand.letter {
+            return false;
+        }
+    }
+    true
+}
+
+/// Estimate how many more guesses finding the answer will take, guessing the top
+/// suggestion next and supposing each later guess splits the candidates into
+/// groups as small, on average, as that one does
+fn expected_guesses<L: Tile>(
+    scores: &BTreeMap<i32, Vec<Word>>,
+    feedback: fn(Word, Word) -> GuessResult<L>,
+) -> f64 {
+    let candidates: Vec<Word> = scores.values().flatten().copied().collect();
+    let guess = match candidates[..] {
+        [] => return 0.0,
+        [_] => return 1.0,
+        [guess, ..] => guess,
+    };
+    let n = candidates.len() as f64;
+    let groups = candidates
+        .iter()
+        .filter(|&&c| c != guess)
+        .counts_by(|&c| feedback(guess, c).pattern());
+    // The average size of the group the answer ends up in, for how much a guess narrows it down
+    let mean_group = groups.values().map(|&k| (k * k) as f64).sum::<f64>() / n;
+    let split = (n / mean_group).max(2.0);
+    let remaining = |k: usize| 1.0 + (k as f64).ln() / split.ln();
+    1.0 + groups
+        .values()
+        .map(|&k| k as f64 / n * remaining(k))
+        .sum::<f64>()
+}
+
+/// Show how many guesses the answer should take, so there's warning of running out
+fn display_pace<L: Tile>(
+    scores: &BTreeMap<i32, Vec<Word>>,
+    guessed: usize,
+    feedback: fn(Word, Word) -> GuessResult<L>,
+) {
+    if scores.is_empty() {
+        return;
+    }
+    let expected = expected_guesses(scores, feedback);
+    let total = guessed as f64 + expected;
+    println!(
+        "Expect about {:.1} more guesses, finishing on guess {:.1}{}",
+        expected,
+        total,
+        if total > MAX_GUESSES as f64 {
+            format!(", which is past the {} allowed", MAX_GUESSES)
+        } else {
+            String::new()
+        }
+    );
+}
+
+/// Score the words that are candidates by how common their letters are among them.
+/// `counts` are the letter frequencies of all the words, if they're known without
+/// counting, for when every word is still a candidate.
+pub fn suggest(
+    words: &[Word],
+    alphabet: &Alphabet,
+    counts: Option<&[i32]>,
+    is_candidate: impl Fn(&Word) -> bool + Sync,
+) -> (Vec<Word>, BTreeMap<i32, Vec<Word>>) {
+    let all = words.len();
+    // Find the subset of possible matches based on the available hints. Huge
+    // custom lists are split across threads, keeping the words in order.
+    let words: Vec<Word> = if words.len() < strategy::PARALLEL_WORDS {
+        words
+            .iter()
+            .filter(|&word| is_candidate(word))
+            .copied()
+            .collect()
+    } else {
+        words
+            .par_iter()
+            .filter(|&word| is_candidate(word))
+            .copied()
+            .collect()
+    };
+
+    // Determine the frequencies
+    let freq = match counts {
+        Some(counts) if words.len() == all => counts.to_vec(),
+        _ => strategy::letter_frequencies(&words, alphabet),
+    };
+    let score = |word: &Word| -strategy::frequency_score(word, alphabet, &freq);
+    let word_scores: Vec<i32> = if words.len() < strategy::PARALLEL_WORDS {
+        words.iter().map(score).collect()
+    } else {
+        words.par_iter().map(score).collect()
+    };
+
+    // We really want this map to be ordered by highest score, but that requires
+    // implementing a wrapper type around numbers. It's easier to just negate the
+    // score so the map is ordered as desired.
+    let mut scores: BTreeMap<i32, Vec<Word>> = BTreeMap::new();
+    words
+        .iter()
+        .zip(word_scores)
+        .for_each(|(&word, score)| scores.entry(score).or_default().push(word));
+
+    (words, scores)
+}
+
+/// The best suggestions, skipping any that share `SIMILAR_LETTERS` letters with a
+/// better one that's kept. If too few are left, the best of those skipped make up
+/// the numbers.
+fn diversify(scores: &BTreeMap<i32, Vec<Word>>) -> BTreeMap<i32, Vec<Word>> {
+    let shared = |a: &Word, b: &Word| {
+        let mut rest = b.0.to_vec();
+        a.0.iter()
+            .filter(|c| match rest.iter().position(|r| r == *c) {
+                Some(i) => {
+                    rest.swap_remove(i);
+                    true
+                }
+                None => false,
+            })
+            .count()
+    };
+    let ranked: Vec<(i32, Word)> = scores
+        .iter()
+        .flat_map(|(&score, words)| words.iter().map(move |&w| (score, w)))
+        .collect();
+    let mut kept: Vec<(i32, Word)> = vec![];
+    let mut skipped = vec![];
+    for (score, word) in ranked {
+        if kept.len() == DIVERSE_SUGGESTIONS {
+            break;
+        }
+        match kept
+            .iter()
+            .any(|(_, k)| shared(k, &word) >= SIMILAR_LETTERS)
+        {
+            true => skipped.push((score, word)),
+            false => kept.push((score, word)),
+        }
+    }
+    let missing = DIVERSE_SUGGESTIONS.saturating_sub(kept.len());
+    kept.extend(skipped.into_iter().take(missing));
+
+    let mut diverse: BTreeMap<i32, Vec<Word>> = BTreeMap::new();
+    for (score, word) in kept {
+        diverse.entry(score).or_default().push(word);
+    }
+    diverse
+}
+
+/// The best suggestions, from the highest score
+fn top_suggestions(scores: &BTreeMap<i32, Vec<Word>>) -> Vec<Word> {
+    scores.values().flatten().take(10).copied().collect()
+}
+
+pub fn display_suggestions(scores: &BTreeMap<i32, Vec<Word>>, coverage: Option<&Coverage>) {
+    // Display the top suggestions
+    println!("Suggestions, in ascending order of score:");
+    let mut lines = vec![];
+    // Each word is numbered for `use`, from the best
+    let mut number = 0;
+    let width = console::Term::stdout()
+        .size_checked()
+        .map_or(DEFAULT_WIDTH, |(_, columns)| columns as usize);
+    for (score, words) in scores.iter().take(10) {
+        let prefix = format!("{:5} -> ", -score);
+        let coverage = match coverage {
+            Some(coverage) => coverage,
+            None => {
+                let row = word_columns(words, width.saturating_sub(prefix.len()));
+                lines.push(format!("{}{}", prefix, row));
+                continue;
+            }
+        };
+        // Words with the same score are nearly always anagrams, testing the same
+        // letters, so each run of them shares a row
+        let mut rest = &words[..];
+        while let Some(first) = rest.first() {
+            let description = coverage.describe(first);
+            let run = rest
+                .iter()
+                .take_while(|word| coverage.describe(word) == description)
+                .count();
+            let cells: Vec<String> = rest[..run]
+                .iter()
+                .map(|word| {
+                    number += 1;
+                    format!("#{} {}", number, word)
+                })
+                .collect();
+            let suffix = format!("  {}", description);
+            let row = columns(&cells, width.saturating_sub(prefix.len() + suffix.len()));
+            lines.push(format!("{}{}{}", prefix, row, suffix));
+            rest = &rest[run..];
+        }
+    }
+    lines.iter().rev().for_each(|line| println!("{}", line));
+}
+
+/// The words in aligned columns, as many as fit in `width`, and how many more
+/// there are if they don't all fit. At least one word is always shown.
+fn word_columns(words: &[Word], width: usize) -> String {
+    let cells: Vec<String> = words.iter().map(Word::to_string).collect();
+    columns(&cells, width)
+}
+
+/// The cells in aligned columns, like [word_columns]
+fn columns(cells: &[String], width: usize) -> String {
+    let cell_width = cells.iter().map(|c| c.chars().count()).max().unwrap_or(0) + COLUMN_GAP;
+    let cell = |text: &String| format!("{:<1$}", text, cell_width);
+    if cells.len() * cell_width <= width + COLUMN_GAP {
+        return cells
+            .iter()
+            .map(cell)
+            .collect::<String>()
+            .trim_end()
+            .to_string();
+    }
+    let mut shown = cells.len() - 1;
+    let more = |shown: usize| format!("(+{} more)", cells.len() - shown);
+    while shown > 1 && shown * cell_width + more(shown).len() > width {
+        shown -= 1;
+    }
+    cells[..shown].iter().map(cell).collect::<String>() + more(shown).as_str()
+}
+
+/// Which letters the results have shown to be in the answer or not, for seeing
+/// what a guess would find out
+pub struct Coverage {
+    absent: Vec<char>,
+    present: Vec<char>,
+    /// Letters whose place in the answer has been found
+    correct: Vec<char>,
+}
+
+impl Coverage {
+    fn new(entries: &Entries) -> Self {
+        let (mut absent, mut present, mut correct) = (vec![], vec![], vec![]);
+        for (_, constraints) in entries {
+            absent.extend(&constraints.omit_letters);
+            present.extend(&constraints.req_letters);
+            correct.extend(
+                constraints
+                    .cand_letters
+                    .iter()
+                    .filter(|cand| cand.correct_location)
+                    .map(|cand| cand.letter),
+            );
+        }
+        Coverage {
+            absent,
+            present,
+            correct,
+        }
+    }
+
+    /// The state of each symbol of the alphabet, like the keys of the game's keyboard
+    fn keys(&self, alphabet: &Alphabet) -> Vec<GuessLetter> {
+        alphabet
+            .symbols()
+            .iter()
+            .map(|&c| {
+                if self.correct.contains(&c) {
+                    GuessLetter::Correct(c)
+                } else if self.present.contains(&c) {
+                    GuessLetter::Present(c)
+                } else if self.absent.contains(&c) {
+                    GuessLetter::Incorrect(c)
+                } else {
+                    GuessLetter::Empty
+                }
+            })
+            .collect()
+    }
+
+    /// The letters the guess would test, and how many of them nothing is known about yet.
+    /// A letter known to be in the answer is still tested for where it goes.
+    fn describe(&self, word: &Word) -> String {
+        let tested: Vec<char> = word
+            .0
+            .iter()
+            .copied()
+            .unique()
+            .filter(|c| !self.absent.contains(c))
+            .sorted()
+            .collect();
+        let new = tested.iter().filter(|c| !self.present.contains(c)).count();
+        format!(
+            "tests {}; {} new",
+            tested.iter().map(|c| c.to_ascii_uppercase()).join(", "),
+            new
+        )
+    }
+}
+
+/// The words in the list
+#[cfg(test)]
+fn words(list: &[&str]) -> Vec<Word> {
+    list.iter().map(|&w| w.try_into().unwrap()).collect()
+}
+
+/// The results with their constraints, as the solver keeps them
+#[cfg(test)]
+fn entries(lines: &[&str]) -> Entries {
+    let alphabet = Alphabet::letters();
+    lines
+        .iter()
+        .map(|&line| {
+            (
+                line.to_string(),
+                Constraints::parse(line, &alphabet).unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_peaks_candidate() {
+    use crate::PeaksLetter;
+
+    let alphabet = Alphabet::letters();
+    let answer: Word = "crane".try_into().unwrap();
+    let mut ranges = [(0, alphabet.len()); 5];
+    for (pos, letter) in peaks_word("stomp".try_into().unwrap(), answer)
+        .0
+        .iter()
+        .enumerate()
+    {
+        match *letter {
+            PeaksLetter::Earlier(c) => ranges[pos].1 = alphabet.index(c).unwrap(),
+            PeaksLetter::Later(c) => ranges[pos].0 = alphabet.index(c).unwrap() + 1,
+            PeaksLetter::Correct(c) => {
+                ranges[pos] = (alphabet.index(c).unwrap(), alphabet.index(c).unwrap() + 1)
+            }
+            PeaksLetter::Empty => unreachable!(),
+        }
+    }
+    assert!(is_peaks_candidate(&answer, &alphabet, &ranges));
+    assert!(!is_peaks_candidate(
+        &"truck".try_into().unwrap(),
+        &alphabet,
+        &ranges
+    ));
+}
+
+#[test]
+fn test_find_probe() {
+    let candidates = words(&["fight", "light", "might", "night", "sight"]);
+    let accepted = words(&["crane", "flags", "fight", "mills"]);
+    let (probe, letters) = find_probe(&candidates, &accepted).unwrap();
+    assert_eq!(probe.to_string(), "flags");
+    assert_eq!(letters, ['f', 'l', 's']);
+
+    let candidates = words(&["crane", "crate"]);
+    assert!(find_probe(&candidates, &accepted).is_none());
+}
+
+#[test]
+fn test_plan() {
+    let candidates = words(&["fight", "light", "might", "night", "sight"]);
+    let accepted = words(&["crane", "flags", "mills", "fight", "nymph"]);
+    let script = plan(&candidates, &accepted, &['i', 'g', 'h', 't'], 3);
+    let guesses: Vec<String> = script.iter().map(|(w, _)| w.to_string()).collect();
+    // "flags" and "mills" test as much as each other, so the first listed is picked
+    assert_eq!(guesses, ["flags", "nymph"]);
+    assert_eq!(script[0].1, ['f', 'l', 's']);
+    assert_eq!(script[1].1, ['n', 'm']);
+}
+
+#[test]
+fn test_position_entropy() {
+    let positions = position_entropy(&words(&[
+        "fight", "light", "might", "night", "sight", "sighs",
+    ]));
+    assert_eq!(positions[0].0.len(), 5);
+    assert_eq!(positions[0].0[0], 's');
+    assert_eq!(positions[1], (vec!['i'], 0.0));
+    assert_eq!(positions[4].0, ['t', 's']);
+    assert!((positions[4].1 - 0.650).abs() < 1e-3);
+}
+
+#[test]
+fn test_exact_letters() {
+    let constraints = Constraints::parse("!ze!e", &Alphabet::letters()).unwrap();
+    assert!(is_candidate(&"crane".try_into().unwrap(), &constraints));
+    assert!(!is_candidate(&"geese".try_into().unwrap(), &constraints));
+    assert!(!is_candidate(&"fetch".try_into().unwrap(), &constraints));
+}
+
+#[test]
+fn test_drop_command() {
+    let mut entries = entries(&["=cr!a!n!e", "!s!l!o!t=h"]);
+    assert_eq!(
+        entries[0].1.descriptions(),
+        [
+            "no 'a'",
+            "no 'n'",
+            "no 'e'",
+            "contains 'c'",
+            "contains 'r'",
+            "'c' in position 1",
+            "'r' not in position 2"
+        ]
+    );
+    drop_command("drop 9", &mut entries).unwrap().unwrap();
+    assert_eq!(entries[1].1.descriptions()[..2], ["no 's'", "no 'o'"]);
+    assert!(drop_command("drop 0", &mut entries).unwrap().is_err());
+    assert!(drop_command("drop 99", &mut entries).unwrap().is_err());
+    assert!(drop_command("crane", &mut entries).is_none());
+
+    retract_command("retract-guess 1", &mut entries)
+        .unwrap()
+        .unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].0, "!s!l!o!t=h");
+    assert!(retract_command("retract-guess 2", &mut entries)
+        .unwrap()
+        .is_err());
+}
+
+#[test]
+fn test_position_summary() {
+    let positions = position_summary(&entries(&["=cr!a!n!e", "!s!lr!o!t"]));
+    assert_eq!(positions[0], (Some('c'), vec![]));
+    assert_eq!(positions[1], (None, vec!['r']));
+    assert_eq!(positions[2], (None, vec!['r']));
+    assert_eq!(positions[3], (None, vec![]));
+}
+
+#[test]
+fn test_expected_guesses() {
+    let alphabet = Alphabet::letters();
+    let scores = |list: &[&str]| suggest(&words(list), &alphabet, None, |_| true).1;
+    assert_eq!(expected_guesses(&scores(&["crane"]), guess_word), 1.0);
+    // Whichever is guessed first, the other is certain to be next
+    assert_eq!(
+        expected_guesses(&scores(&["crane", "crate"]), guess_word),
+        1.5
+    );
+}
+
+#[test]
+fn test_chances() {
+    let alphabet = Alphabet::letters();
+    let words = words(&["eerie", "fuzzy", "jazzy", "crane"]);
+    let uniform = Prior::new(&words, &alphabet, false).chances(&words[1..3]);
+    assert!(uniform.iter().all(|&(_, chance)| chance == 0.5));
+    let weighted = Prior::new(&words, &alphabet, true).chances(&words[1..3]);
+    assert_eq!(weighted[0].0.to_string(), "jazzy");
+    assert!((weighted.iter().map(|&(_, c)| c).sum::<f64>() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_coverage() {
+    let alphabet = Alphabet::letters();
+    let coverage = Coverage::new(&entries(&["!crane"]));
+    assert_eq!(
+        coverage.describe(&"spilt".try_into().unwrap()),
+        "tests I, L, P, S, T; 5 new"
+    );
+    assert_eq!(
+        coverage.describe(&"cocoa".try_into().unwrap()),
+        "tests A, O; 1 new"
+    );
+    let keys = coverage.keys(&alphabet);
+    assert!(matches!(keys[0], GuessLetter::Present('a')));
+    assert!(matches!(keys[1], GuessLetter::Empty));
+    assert!(matches!(keys[2], GuessLetter::Incorrect('c')));
+}
+
+#[test]
+fn test_use_command() {
+    let alphabet = Alphabet::letters();
+    let words = words(&["crane", "fuzzy"]);
+    let (_, scores) = suggest(&words, &alphabet, None, |_| true);
+    assert_eq!(use_command("use #1", &scores).unwrap().unwrap(), words[0]);
+    assert_eq!(use_command("use 2", &scores).unwrap().unwrap(), words[1]);
+    assert!(use_command("use 3", &scores).unwrap().is_err());
+    assert!(use_command("crane", &scores).is_none());
+    assert_eq!(
+        spell_result(words[0], "gyb.b", wordle_feedback).unwrap(),
+        "=cr!a!n!e"
+    );
+}
+
+#[test]
+fn test_correct_mistake() {
+    let alphabet = Alphabet::letters();
+    let words = words(&["crane", "slate", "trace"]);
+    // "crane" against "trace", entered with the a as gray rather than green
+    let entries = entries(&["cR!a!nE", "!s!lAtE"]);
+    let (index, (line, _)) = correct_mistake(&words, &entries, &alphabet).unwrap();
+    assert_eq!(index, 0);
+    assert_eq!(line, "cR=a!nE");
+    assert_eq!(
+        result_tiles("!s!l=At=e").unwrap(),
+        ["!s", "!l", "=A", "t", "=e"]
+    );
+    assert!(result_tiles("!s!l!a!t!").is_none());
+}
+
+#[test]
+fn test_is_adversarial() {
+    let alphabet = Alphabet::letters();
+    let words = words(&[
+        "crane", "bills", "hills", "mills", "pills", "fills", "kills",
+    ]);
+    // Both results leave the largest group there could be
+    assert!(is_adversarial(
+        &words,
+        &entries(&["!c!r!a!n!e", "!b!u!m!p!h"]),
+        &alphabet
+    ));
+    assert!(!is_adversarial(
+        &words,
+        &entries(&["!c!r!a!n!e"]),
+        &alphabet
+    ));
+    // "bills" was alone in its group
+    assert!(!is_adversarial(
+        &words,
+        &entries(&["!c!r!a!n!e", "B!u!m!p!h"]),
+        &alphabet
+    ));
+    // The guess behind a result with a placeholder isn't known, so only one result counts
+    assert!(!is_adversarial(
+        &words,
+        &entries(&["!c!r?!n!e", "!b!u!m!p!h"]),
+        &alphabet
+    ));
+    // Any of the -ills words leaves the other five together, and "crane" all six
+    let scores = minimax_scores(&words);
+    assert_eq!(scores.keys().collect::<Vec<_>>(), [&5, &6]);
+}
+
+#[test]
+fn test_whatif_command() {
+    let alphabet = Alphabet::letters();
+    let line = |line| whatif_command(line, &alphabet, wordle_feedback);
+    assert_eq!(line("whatif crane gybb.").unwrap().unwrap(), "=cr!a!n!e");
+    assert!(line("whatif crane gyb").unwrap().is_err());
+    assert!(line("crane").is_none());
+}
+
+#[test]
+fn test_word_columns() {
+    let words = words(&["crane", "slate", "trace", "crate"]);
+    assert_eq!(word_columns(&words, 80), "crane  slate  trace  crate");
+    assert_eq!(word_columns(&words, 26), "crane  slate  trace  crate");
+    assert_eq!(word_columns(&words, 25), "crane  slate  (+2 more)");
+    assert_eq!(word_columns(&words, 5), "crane  (+3 more)");
+    let cells = ["#9 learn".to_string(), "#10 renal".to_string()];
+    assert_eq!(columns(&cells, 80), "#9 learn   #10 renal");
+    assert_eq!(columns(&cells, 15), "#9 learn   (+1 more)");
+}
+
+#[test]
+fn test_check_consistent() {
+    let alphabet = Alphabet::letters();
+    let entries = entries(&["!cR!a!nE"]);
+    let check =
+        |line| check_consistent(&entries, line, Constraints::parse(line, &alphabet).unwrap());
+    assert!(check("!s!lAtE").is_err());
+    assert!(check("!sTo!rE").is_err());
+    assert!(check("!sh!o!ut").is_ok());
+    assert!(matches!(
+        Constraints::parse("cran$e", &alphabet),
+        Err(WordleError::InvalidResult(_))
+    ));
+}
+
+#[test]
+fn test_diversify() {
+    let scores = |words: &[&str]| -> BTreeMap<i32, Vec<Word>> {
+        words
+            .iter()
+            .enumerate()
+            .map(|(i, &w)| (i as i32, vec![w.try_into().unwrap()]))
+            .collect()
+    };
+    let shown = |scores: BTreeMap<i32, Vec<Word>>| {
+        diversify(&scores)
+            .values()
+            .flatten()
+            .map(|w| w.to_string())
+            .join(" ")
+    };
+    let few = ["crane", "caner", "slate", "nacre", "stale", "pious"];
+    // With too few different words, the anagrams still make up the numbers
+    assert_eq!(shown(scores(&few)), few.join(" "));
+
+    let mut many = few.to_vec();
+    many.extend([
+        "dumpy", "wight", "jokey", "fluff", "civic", "vodka", "mirth", "bongo",
+    ]);
+    assert_eq!(
+        shown(scores(&many)),
+        "crane slate pious dumpy wight jokey fluff civic vodka mirth"
+    );
+}
+
+#[test]
+fn test_pasted_result() {
+    let alphabet = Alphabet::letters();
+    let pasted = |line| pasted_result(line, &alphabet);
+    assert_eq!(
+        pasted("C r a n e: green, yellow, gray, gray, gray").as_deref(),
+        Some("=cr!a!n!e")
+    );
+    assert_eq!(pasted("crane: 🟩🟨⬛⬛⬛").as_deref(), Some("=cr!a!n!e"));
+    assert_eq!(pasted("C, r, !a, !n, !e").as_deref(), Some("Cr!a!n!e"));
+    assert_eq!(pasted("crane: green, yellow"), None);
+    assert_eq!(
+        pasted_feedback("Green Grey YELLOW grey grey").as_deref(),
+        Some("gbybb")
+    );
+}
@@ -0,0 +1,220 @@
+use crate::dictionary::Alphabet;
+use crate::strategy::Frequencies;
+use crate::{words, Word};
+use anyhow::{anyhow, Context, Result};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+/// Somewhere a game's word lists are loaded from. Every command loads its words
+/// through a source, so a new kind of list only needs a new source.
+pub trait WordSource {
+    /// The symbols the words are spelled with
+    fn alphabet(&self) -> &Alphabet;
+
+    /// The words that can be the answer
+    fn answers(&self) -> Result<Vec<Word>>;
+
+    /// Words that are accepted as guesses, but can't be the answer
+    fn extra(&self) -> Result<Vec<Word>> {
+        Ok(vec![])
+    }
+
+    /// The answers and extra words together, if they're already sorted so they
+    /// don't need sorting for checking guesses
+    fn accepted(&self) -> Option<Vec<Word>> {
+        None
+    }
+
+    /// How often each symbol appears in the answers and in the extra words, if
+    /// that's known without counting them
+    fn frequencies(&self) -> Option<(Frequencies, Frequencies)> {
+        None
+    }
+}
+
+/// How often each symbol appears in a built-in list, overall and in each position,
+/// as counted when building
+type Counts = (&'static [i32], [&'static [i32]; 5]);
+
+/// Lists built into the binary, which were checked and counted when building
+pub struct Builtin {
+    alphabet: Alphabet,
+    answers: &'static [&'static str],
+    extra: &'static [&'static str],
+    /// Both lists together, sorted when building
+    accepted: &'static [&'static str],
+    answer_counts: Counts,
+    /// `None` when there are no extra words to count
+    extra_counts: Option<Counts>,
+}
+
+impl Builtin {
+    pub fn english() -> Self {
+        Builtin {
+            alphabet: Alphabet::letters(),
+            answers: words::WORDS_USED,
+            extra: words::WORDS_XTRA,
+            accepted: words::WORDS_ACCEPTED,
+            answer_counts: (words::WORDS_USED_LETTERS, words::WORDS_USED_POSITIONS),
+            extra_counts: Some((words::WORDS_XTRA_LETTERS, words::WORDS_XTRA_POSITIONS)),
+        }
+    }
+
+    pub fn primel() -> Self {
+        Builtin {
+            alphabet: Alphabet::digits(),
+            answers: words::PRIMES,
+            extra: &[],
+            // The primes are generated in order
+            accepted: words::PRIMES,
+            answer_counts: (words::PRIMES_LETTERS, words::PRIMES_POSITIONS),
+            extra_counts: None,
+        }
+    }
+}
+
+impl WordSource for Builtin {
+    fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    fn answers(&self) -> Result<Vec<Word>> {
+        Ok(parse_builtin(self.answers))
+    }
+
+    fn extra(&self) -> Result<Vec<Word>> {
+        Ok(parse_builtin(self.extra))
+    }
+
+    fn accepted(&self) -> Option<Vec<Word>> {
+        Some(parse_builtin(self.accepted))
+    }
+
+    fn frequencies(&self) -> Option<(Frequencies, Frequencies)> {
+        let counted = |(letters, positions): Counts| Frequencies::from_counts(letters, positions);
+        let extra = match self.extra_counts {
+            Some(counts) => counted(counts),
+            None => Frequencies::count(&[], &self.alphabet),
+        };
+        Some((counted(self.answer_counts), extra))
+    }
+}
+
+/// Lists kept in files, one word per line
+pub struct FileSource {
+    alphabet: Alphabet,
+    answers: PathBuf,
+    extra: Option<PathBuf>,
+}
+
+impl FileSource {
+    pub fn new(answers: &Path, alphabet: Alphabet) -> Self {
+        FileSource {
+            alphabet,
+            answers: answers.to_path_buf(),
+            extra: None,
+        }
+    }
+
+    /// Also read the words that are accepted, but can't be the answer, from a file
+    pub fn with_extra(mut self, extra: &Path) -> Self {
+        self.extra = Some(extra.to_path_buf());
+        self
+    }
+}
+
+impl WordSource for FileSource {
+    fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    fn answers(&self) -> Result<Vec<Word>> {
+        let answers = read_words(&self.answers, &self.alphabet)?;
+        if answers.is_empty() {
+            return Err(anyhow!("{} contains no words", self.answers.display()));
+        }
+        Ok(answers)
+    }
+
+    fn extra(&self) -> Result<Vec<Word>> {
+        match &self.extra {
+            Some(path) => read_words(path, &self.alphabet),
+            None => Ok(vec![]),
+        }
+    }
+}
+
+/// Read a list of words, one per line, skipping blank lines
+fn read_words(path: &Path, alphabet: &Alphabet) -> Result<Vec<Word>> {
+    let file = File::open(path).with_context(|| format!("opening {}", path.display()))?;
+    let mut words = vec![];
+    for (line_no, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.with_context(|| format!("reading {}", path.display()))?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let word = alphabet
+            .parse_word(line)
+            .with_context(|| format!("{}:{}", path.display(), line_no + 1))?;
+        words.push(word);
+    }
+    Ok(words)
+}
+
+fn parse_builtin(words: &[&str]) -> Vec<Word> {
+    words
+        .iter()
+        .map(|&s| Word::try_from(s))
+        .map(Result::unwrap)
+        .collect()
+}
+
+#[test]
+fn test_file_source() {
+    let dir = std::env::temp_dir().join(format!("wordle_solve_source_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (answers, extra, empty) = (dir.join("answers"), dir.join("extra"), dir.join("empty"));
+    std::fs::write(&answers, "cigar\n\nRebut\n").unwrap();
+    std::fs::write(&extra, "aahed\n").unwrap();
+    std::fs::write(&empty, "\n").unwrap();
+
+    let source = FileSource::new(&answers, Alphabet::letters()).with_extra(&extra);
+    let words = |words: Vec<Word>| words.iter().map(Word::to_string).collect::<Vec<_>>();
+    assert_eq!(words(source.answers().unwrap()), ["cigar", "rebut"]);
+    assert_eq!(words(source.extra().unwrap()), ["aahed"]);
+    assert!(FileSource::new(&empty, Alphabet::letters())
+        .answers()
+        .is_err());
+    assert!(FileSource::new(&answers, Alphabet::digits())
+        .answers()
+        .is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_builtin_frequencies() {
+    use crate::dictionary::Dictionary;
+    for (source, new) in [(Builtin::english(), "aahed"), (Builtin::primel(), "10000")] {
+        let mut dict = Dictionary::load(&source).unwrap();
+        for more_words in [false, true] {
+            let counted = Frequencies::count(&dict.words(more_words), &dict.alphabet);
+            assert_eq!(dict.frequencies(more_words), counted);
+        }
+        // Once the answers change, they're counted instead
+        dict.add_answer(dict.alphabet.parse_word(new).unwrap());
+        let counted = Frequencies::count(&dict.words(false), &dict.alphabet);
+        assert_eq!(dict.frequencies(false), counted);
+    }
+}
+
+#[test]
+fn test_builtin_accepted() {
+    for source in [Builtin::english(), Builtin::primel()] {
+        let accepted = source.accepted().unwrap();
+        assert!(accepted.windows(2).all(|pair| pair[0] < pair[1]));
+        let words = source.answers().unwrap().len() + source.extra().unwrap().len();
+        assert_eq!(accepted.len(), words);
+    }
+}
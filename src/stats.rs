@@ -0,0 +1,621 @@
+use crate::{Word, MAX_GUESSES};
+use anyhow::{anyhow, Context, Result};
+use chrono::{Local, NaiveDate, Utc};
+use chrono_tz::Tz;
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The database every profile's finished games and solve sessions are kept in
+const DATABASE_FILENAME: &str = "history.db";
+
+/// The file in each profile's directory that games were appended to before there
+/// was a database. They're imported when the database is created.
+const LEGACY_FILENAME: &str = "history.tsv";
+
+/// One person's stats, kept apart so people sharing a machine don't break each
+/// other's streaks
+pub struct Profile {
+    pub name: String,
+}
+
+impl Profile {
+    pub fn open(name: &str) -> Result<Self> {
+        let valid = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+        if name.is_empty() || !name.chars().all(valid) {
+            return Err(anyhow!(
+                "Invalid profile name \"{}\" (use letters, digits, '-' and '_')",
+                name
+            ));
+        }
+        Ok(Profile {
+            name: name.to_string(),
+        })
+    }
+
+    /// Every profile that has played a game or solved one, by name
+    pub fn all() -> Result<Vec<Self>> {
+        let db = open_database()?;
+        let mut query = db.prepare(
+            "SELECT profile FROM games UNION SELECT profile FROM sessions ORDER BY profile",
+        )?;
+        let names = query.query_map([], |row| row.get::<_, String>(0))?;
+        names
+            .map(|name| Profile::open(&name?))
+            .collect::<Result<_>>()
+    }
+
+    pub fn exists(&self) -> Result<bool> {
+        let db = open_database()?;
+        let exists = db.query_row(
+            "SELECT EXISTS (SELECT 1 FROM games WHERE profile = ?1)
+                 OR EXISTS (SELECT 1 FROM sessions WHERE profile = ?1)",
+            [&self.name],
+            |row| row.get(0),
+        )?;
+        Ok(exists)
+    }
+
+    /// Delete the profile's games and solve sessions
+    pub fn delete(&self) -> Result<()> {
+        let db = open_database()?;
+        db.execute("DELETE FROM games WHERE profile = ?1", [&self.name])?;
+        db.execute("DELETE FROM sessions WHERE profile = ?1", [&self.name])?;
+        db.execute("DELETE FROM relay_rows WHERE profile = ?1", [&self.name])?;
+        Ok(())
+    }
+
+    /// Add a finished game to the profile's history
+    pub fn record(&self, game: &GameRecord) -> Result<()> {
+        insert_game(&open_database()?, &self.name, game)
+    }
+
+    /// Every game the profile has finished, oldest first
+    pub fn history(&self) -> Result<Vec<GameRecord>> {
+        let db = open_database()?;
+        let mut query = db.prepare(
+            "SELECT time, mode, answer, guesses, elapsed FROM games
+             WHERE profile = ?1 ORDER BY time, rowid",
+        )?;
+        let games = query.query_map([&self.name], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<i64>>(3)?,
+                row.get::<_, f64>(4)?,
+            ))
+        })?;
+        games
+            .map(|game| {
+                let (time, mode, answer, guesses, elapsed) = game?;
+                Ok(GameRecord {
+                    time: time as u64,
+                    mode,
+                    answer: Word::try_from(answer.as_str())
+                        .map_err(|e| anyhow!("A recorded answer \"{}\" {}", answer, e))?,
+                    guesses: guesses.map(|g| g as usize),
+                    elapsed: Duration::from_secs_f64(elapsed),
+                })
+            })
+            .collect()
+    }
+
+    /// Keep which rows of a relay game the profile guessed, by their feedback
+    pub fn record_relay_rows(&self, time: u64, rows: &[u8]) -> Result<()> {
+        let db = open_database()?;
+        for &pattern in rows {
+            db.execute(
+                "INSERT INTO relay_rows (profile, time, pattern) VALUES (?1, ?2, ?3)",
+                params![self.name, time as i64, pattern],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// The feedback for every row the profile has guessed in relay games
+    pub fn relay_rows(&self) -> Result<Vec<u8>> {
+        let db = open_database()?;
+        let mut query =
+            db.prepare("SELECT pattern FROM relay_rows WHERE profile = ?1 ORDER BY time, rowid")?;
+        let rows = query.query_map([&self.name], |row| row.get(0))?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Every solve session the profile has finished, oldest first
+    pub fn sessions(&self) -> Result<Vec<SessionRecord>> {
+        let db = open_database()?;
+        let mut query = db.prepare(
+            "SELECT time, variant, results, candidates, answer FROM sessions
+             WHERE profile = ?1 ORDER BY time, rowid",
+        )?;
+        let sessions = query.query_map([&self.name], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?;
+        sessions
+            .map(|session| {
+                let (time, variant, results, candidates, answer) = session?;
+                Ok(SessionRecord {
+                    time: time as u64,
+                    variant,
+                    results: serde_json::from_str(&results)?,
+                    candidates: serde_json::from_str(&candidates)?,
+                    answer: answer
+                        .map(|answer| {
+                            Word::try_from(answer.as_str())
+                                .map_err(|e| anyhow!("A recorded answer \"{}\" {}", answer, e))
+                        })
+                        .transpose()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Add a finished solve session to the profile's history
+    pub fn record_session(&self, session: &SessionRecord) -> Result<()> {
+        let db = open_database()?;
+        db.execute(
+            "INSERT INTO sessions (profile, time, variant, results, candidates, answer)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                self.name,
+                session.time as i64,
+                session.variant,
+                serde_json::to_string(&session.results)?,
+                serde_json::to_string(&session.candidates)?,
+                session.answer.map(|w| w.to_string()),
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+fn open_database() -> Result<Connection> {
+    let dir = data_dir()?;
+    fs::create_dir_all(&dir).with_context(|| format!("creating {}", dir.display()))?;
+    let path = dir.join(DATABASE_FILENAME);
+    let new = !path.exists();
+    let db = Connection::open(&path).with_context(|| format!("opening {}", path.display()))?;
+    db.execute_batch(
+        "CREATE TABLE IF NOT EXISTS games (
+             profile TEXT NOT NULL,
+             time INTEGER NOT NULL,
+             mode TEXT NOT NULL,
+             answer TEXT NOT NULL,
+             guesses INTEGER,
+             elapsed REAL NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS games_by_profile ON games (profile, time);
+         CREATE TABLE IF NOT EXISTS sessions (
+             profile TEXT NOT NULL,
+             time INTEGER NOT NULL,
+             variant TEXT NOT NULL,
+             results TEXT NOT NULL,
+             candidates TEXT NOT NULL,
+             answer TEXT
+         );
+         CREATE TABLE IF NOT EXISTS relay_rows (
+             profile TEXT NOT NULL,
+             time INTEGER NOT NULL,
+             pattern INTEGER NOT NULL
+         );",
+    )
+    .with_context(|| format!("setting up {}", path.display()))?;
+    if new {
+        import_legacy(&db).context("importing the stats kept before the database")?;
+    }
+    Ok(db)
+}
+
+fn insert_game(db: &Connection, profile: &str, game: &GameRecord) -> Result<()> {
+    db.execute(
+        "INSERT INTO games (profile, time, mode, answer, guesses, elapsed)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            profile,
+            game.time as i64,
+            game.mode,
+            game.answer.to_string(),
+            game.guesses.map(|g| g as i64),
+            game.elapsed.as_secs_f64(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Copy each profile's history file into the database, leaving the files alone
+fn import_legacy(db: &Connection) -> Result<()> {
+    let dir = data_dir()?.join("profiles");
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(&dir).with_context(|| format!("reading {}", dir.display()))? {
+        let entry = entry.with_context(|| format!("reading {}", dir.display()))?;
+        let path = entry.path().join(LEGACY_FILENAME);
+        let name = entry.file_name();
+        let name = match name.to_str() {
+            Some(name) if path.exists() => name,
+            _ => continue,
+        };
+        let file = File::open(&path).with_context(|| format!("opening {}", path.display()))?;
+        for (line_no, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.with_context(|| format!("reading {}", path.display()))?;
+            let game = GameRecord::parse(&line)
+                .ok_or_else(|| anyhow!("{}:{}: invalid entry", path.display(), line_no + 1))?;
+            insert_game(db, name, &game)?;
+        }
+    }
+    Ok(())
+}
+
+/// The day of the first Wordle, which was puzzle zero
+const FIRST_DAY: (i32, u32, u32) = (2021, 6, 19);
+
+/// The number of today's daily puzzle in the time zone, or the local one. The puzzle
+/// changes at midnight there, so people who agree on a zone get the same word.
+pub fn todays_puzzle(tz: Option<Tz>) -> u64 {
+    puzzle_on(today(tz))
+}
+
+/// How long until the next puzzle, at the next midnight in the time zone
+pub fn until_next_puzzle(tz: Option<Tz>) -> Duration {
+    let midnight = today(tz)
+        .succ_opt()
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .expect("valid date");
+    let now = match tz {
+        Some(tz) => Utc::now().with_timezone(&tz).naive_local(),
+        None => Local::now().naive_local(),
+    };
+    // Daylight saving changes can make this off by an hour, which is close enough
+    (midnight - now).to_std().unwrap_or_default()
+}
+
+fn today(tz: Option<Tz>) -> NaiveDate {
+    match tz {
+        Some(tz) => Utc::now().with_timezone(&tz).date_naive(),
+        None => Local::now().date_naive(),
+    }
+}
+
+/// The number of the daily puzzle on the date
+pub fn puzzle_on(date: NaiveDate) -> u64 {
+    (date - first_day()).num_days().max(0) as u64
+}
+
+/// The date of a daily puzzle
+pub fn puzzle_date(puzzle: u64) -> NaiveDate {
+    first_day() + chrono::Days::new(puzzle)
+}
+
+fn first_day() -> NaiveDate {
+    let (year, month, day) = FIRST_DAY;
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid date")
+}
+
+/// The time in seconds since the Unix epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn data_dir() -> Result<PathBuf> {
+    let dir = dirs::data_dir().ok_or_else(|| anyhow!("Can't find a directory for stats"))?;
+    Ok(dir.join("wordle_solve"))
+}
+
+/// A finished game, as kept in a profile's history
+pub struct GameRecord {
+    /// When the game finished, in seconds since the Unix epoch
+    pub time: u64,
+    /// How the answer was chosen, such as "random" or "challenge"
+    pub mode: String,
+    pub answer: Word,
+    /// `None` if the player ran out of guesses
+    pub guesses: Option<usize>,
+    pub elapsed: Duration,
+}
+
+impl GameRecord {
+    pub fn new(mode: &str, answer: Word, guesses: Option<usize>, elapsed: Duration) -> Self {
+        GameRecord {
+            time: now(),
+            mode: mode.to_string(),
+            answer,
+            guesses,
+            elapsed,
+        }
+    }
+
+    pub fn daily(puzzle: u64) -> String {
+        format!("daily-{}", puzzle)
+    }
+
+    /// The number of the daily puzzle this game was, if it was one
+    pub fn daily_puzzle(&self) -> Option<u64> {
+        self.mode.strip_prefix("daily-")?.parse().ok()
+    }
+
+    /// Read a game from a line of a history file from before the database. A
+    /// number of guesses no game could take, or a time that can't be, is invalid.
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let time = fields.next()?.parse().ok()?;
+        let mode = fields.next()?.to_string();
+        let answer = Word::try_from(fields.next()?).ok()?;
+        let guesses = match fields.next()? {
+            "X" => None,
+            guesses => match guesses.parse().ok()? {
+                guesses @ 1..=MAX_GUESSES => Some(guesses),
+                _ => return None,
+            },
+        };
+        let elapsed = Duration::try_from_secs_f64(fields.next()?.parse().ok()?).ok()?;
+        Some(GameRecord {
+            time,
+            mode,
+            answer,
+            guesses,
+            elapsed,
+        })
+    }
+}
+
+/// A finished solve session, as kept in a profile's history
+pub struct SessionRecord {
+    /// When the session finished, in seconds since the Unix epoch
+    pub time: u64,
+    /// Which game was being solved, such as "wordle" or "peaks"
+    pub variant: String,
+    /// Each result entered, as it was typed
+    pub results: Vec<String>,
+    /// How many candidates there were each round
+    pub candidates: Vec<usize>,
+    /// `None` if the session ended without the answer being known
+    pub answer: Option<Word>,
+}
+
+impl SessionRecord {
+    pub fn new(
+        variant: &str,
+        results: Vec<String>,
+        candidates: Vec<usize>,
+        answer: Option<Word>,
+    ) -> Self {
+        SessionRecord {
+            time: now(),
+            variant: variant.to_string(),
+            results,
+            candidates,
+            answer,
+        }
+    }
+}
+
+/// A summary of a profile's history
+pub struct Stats {
+    pub played: usize,
+    pub won: usize,
+    /// Consecutive games won up to the latest one
+    pub current_streak: usize,
+    pub max_streak: usize,
+    /// How many games were won with each number of guesses, starting from one
+    pub distribution: [usize; MAX_GUESSES],
+}
+
+impl Stats {
+    pub fn new(games: &[GameRecord]) -> Self {
+        let mut stats = Stats {
+            played: games.len(),
+            won: 0,
+            current_streak: 0,
+            max_streak: 0,
+            distribution: [0; MAX_GUESSES],
+        };
+        for game in games {
+            match game.guesses {
+                Some(guesses) => {
+                    stats.won += 1;
+                    stats.current_streak += 1;
+                    stats.max_streak = stats.max_streak.max(stats.current_streak);
+                    stats.distribution[guesses - 1] += 1;
+                }
+                None => stats.current_streak = 0,
+            }
+        }
+        stats
+    }
+
+    pub fn display(&self) {
+        let percent = (100 * self.won).checked_div(self.played).unwrap_or(0);
+        println!(
+            "Played {}, won {}%, current streak {}, max streak {}",
+            self.played, percent, self.current_streak, self.max_streak
+        );
+        let most = self.distribution.iter().copied().max().unwrap_or(0).max(1);
+        for (i, &count) in self.distribution.iter().enumerate() {
+            println!("{} {:<30} {}", i + 1, "#".repeat(count * 30 / most), count);
+        }
+    }
+}
+
+/// The letters whose answers have taken the most guesses, worst first, for practicing.
+/// Only letters seen in a few games count, so one bad game doesn't decide it.
+pub fn weak_letters(games: &[GameRecord], count: usize) -> Vec<char> {
+    const MIN_GAMES: usize = 3;
+    let mut letters: BTreeMap<char, (usize, usize)> = BTreeMap::new();
+    for game in games {
+        let guesses = game.guesses.unwrap_or(MAX_GUESSES + 1);
+        for c in game.answer.0.iter().copied().collect::<BTreeSet<char>>() {
+            let (played, total) = letters.entry(c).or_default();
+            *played += 1;
+            *total += guesses;
+        }
+    }
+    let mut average: Vec<(char, f64)> = letters
+        .into_iter()
+        .filter(|&(_, (played, _))| played >= MIN_GAMES)
+        .map(|(c, (played, total))| (c, total as f64 / played as f64))
+        .collect();
+    average.sort_by(|a, b| b.1.total_cmp(&a.1));
+    average.into_iter().take(count).map(|(c, _)| c).collect()
+}
+
+/// The game of a daily puzzle in a profile's history, if it's been played
+pub fn daily_game(games: &[GameRecord], puzzle: u64) -> Option<&GameRecord> {
+    games.iter().find(|g| g.daily_puzzle() == Some(puzzle))
+}
+
+/// A summary of a profile's daily puzzles, for comparing with other profiles
+pub struct DailyStats {
+    pub played: usize,
+    pub won: usize,
+    /// Failed puzzles count as one more guess than allowed
+    pub mean_guesses: f64,
+    /// Consecutive days won up to today or yesterday
+    pub current_streak: usize,
+    pub max_streak: usize,
+    pub mean_time: Duration,
+}
+
+impl DailyStats {
+    pub fn new(games: &[GameRecord], today: u64) -> Self {
+        let mut daily: Vec<(u64, &GameRecord)> = games
+            .iter()
+            .filter_map(|g| Some((g.daily_puzzle()?, g)))
+            .collect();
+        daily.sort_by_key(|&(puzzle, _)| puzzle);
+
+        let mut stats = DailyStats {
+            played: daily.len(),
+            won: 0,
+            mean_guesses: 0.0,
+            current_streak: 0,
+            max_streak: 0,
+            mean_time: Duration::ZERO,
+        };
+        let mut last_won = None;
+        for &(puzzle, game) in &daily {
+            stats.mean_guesses += game.guesses.unwrap_or(MAX_GUESSES + 1) as f64;
+            stats.mean_time += game.elapsed;
+            if game.guesses.is_some() {
+                stats.won += 1;
+                // Skipping a day breaks the streak, just like losing
+                if last_won.is_some_and(|last| last + 1 == puzzle) {
+                    stats.current_streak += 1;
+                } else {
+                    stats.current_streak = 1;
+                }
+                stats.max_streak = stats.max_streak.max(stats.current_streak);
+                last_won = Some(puzzle);
+            } else {
+                stats.current_streak = 0;
+                last_won = None;
+            }
+        }
+        if last_won.is_none_or(|last| last + 1 < today) {
+            stats.current_streak = 0;
+        }
+        if stats.played > 0 {
+            stats.mean_guesses /= stats.played as f64;
+            stats.mean_time /= stats.played as u32;
+        }
+        stats
+    }
+}
+
+#[test]
+fn test_streaks() {
+    let answer: Word = "crane".try_into().unwrap();
+    let games: Vec<GameRecord> = [
+        Some(3),
+        Some(4),
+        None,
+        Some(2),
+        Some(4),
+        Some(6),
+        None,
+        Some(1),
+    ]
+    .iter()
+    .map(|&guesses| GameRecord::new("random", answer, guesses, Duration::ZERO))
+    .collect();
+    let stats = Stats::new(&games);
+    assert_eq!(stats.played, 8);
+    assert_eq!(stats.won, 6);
+    assert_eq!(stats.current_streak, 1);
+    assert_eq!(stats.max_streak, 3);
+    assert_eq!(stats.distribution, [1, 1, 1, 2, 0, 1]);
+
+    let line = "1700000000\tdaily-5\tcrane\tX\t12.5";
+    let game = GameRecord::parse(line).unwrap();
+    assert_eq!(game.guesses, None);
+    assert_eq!(game.daily_puzzle(), Some(5));
+    assert_eq!(game.elapsed, Duration::from_millis(12500));
+    assert!(GameRecord::parse("1700000000\trandom\tcrane\t7\t12.5").is_none());
+    assert!(GameRecord::parse("1700000000\trandom\tcrane\t3\t-1").is_none());
+    assert!(GameRecord::parse("1700000000\trandom\tcrane\t3\tNaN").is_none());
+}
+
+#[test]
+fn test_puzzle_numbers() {
+    let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    assert_eq!(puzzle_on(date(2021, 6, 19)), 0);
+    assert_eq!(puzzle_on(date(2022, 1, 1)), 196);
+}
+
+#[test]
+fn test_weak_letters() {
+    let game = |answer: &str, guesses| {
+        GameRecord::new(
+            "random",
+            answer.try_into().unwrap(),
+            guesses,
+            Duration::ZERO,
+        )
+    };
+    let games = [
+        game("jazzy", None),
+        game("fuzzy", Some(6)),
+        game("pizza", Some(5)),
+        game("crane", Some(2)),
+        game("slate", Some(3)),
+        game("trace", Some(3)),
+    ];
+    assert_eq!(weak_letters(&games, 2), vec!['z', 'a']);
+    assert!(weak_letters(&games[..2], 2).is_empty());
+}
+
+#[test]
+fn test_daily_streaks() {
+    let answer: Word = "crane".try_into().unwrap();
+    let daily = |puzzle, guesses| {
+        GameRecord::new(&GameRecord::daily(puzzle), answer, guesses, Duration::ZERO)
+    };
+    let games = [
+        daily(1, Some(3)),
+        daily(2, Some(4)),
+        daily(3, Some(5)),
+        daily(5, Some(3)),
+        daily(6, Some(3)),
+        GameRecord::new("random", answer, None, Duration::ZERO),
+    ];
+    let stats = DailyStats::new(&games, 7);
+    assert_eq!(stats.played, 5);
+    assert_eq!(stats.max_streak, 3);
+    assert_eq!(stats.current_streak, 2);
+    assert_eq!(stats.mean_guesses, 3.6);
+    assert_eq!(DailyStats::new(&games, 8).current_streak, 0);
+    assert_eq!(daily_game(&games, 5).and_then(|g| g.guesses), Some(3));
+    assert!(daily_game(&games, 4).is_none());
+}
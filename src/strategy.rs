@@ -0,0 +1,453 @@
+use crate::bench::FeedbackMatrix;
+use crate::dictionary::Alphabet;
+use crate::Word;
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
+
+/// The number of distinct feedback patterns for a five-symbol guess
+pub const PATTERNS: usize = 243;
+
+/// How many guesses `Budgeted` times to estimate how long a whole turn would take
+const BUDGET_SAMPLE: usize = 64;
+
+/// `Adaptive` plays its opening while at least this fraction of the answers
+/// (one in this many) are still candidates, which is usually the first guess or two
+const OPENING_SHARE: usize = 50;
+
+/// `Adaptive` plays its endgame once this few candidates are left
+const ENDGAME_CANDIDATES: usize = 10;
+
+/// The fewest words worth splitting across threads when filtering or counting.
+/// With fewer, starting the work on other threads takes longer than it saves.
+pub const PARALLEL_WORDS: usize = 20_000;
+
+/// A way of choosing the next guess from the remaining candidates. Strategies are
+/// shared between the threads of a benchmark sweep.
+pub trait Strategy: Sync {
+    /// The name used to select the strategy on the command line
+    fn name(&self) -> &str;
+
+    /// A one-line summary of how the strategy chooses
+    fn description(&self) -> &str;
+
+    /// Choose the next guess, as an index into the matrix's guesses, given the
+    /// indices of the answers that are still possible. There's always at least
+    /// three candidates, since with fewer it's best to guess one of them.
+    fn choose(&self, matrix: &FeedbackMatrix, alphabet: &Alphabet, candidates: &[usize]) -> usize;
+
+    /// How the strategy goes about choosing for these candidates, for strategies
+    /// that choose differently as the game goes on
+    fn explain(&self, _matrix: &FeedbackMatrix, _candidates: &[usize]) -> Option<String> {
+        None
+    }
+}
+
+/// Choose the next guess with the strategy, guessing a candidate when that's all that's left to do
+pub fn choose(
+    strategy: &dyn Strategy,
+    matrix: &FeedbackMatrix,
+    alphabet: &Alphabet,
+    candidates: &[usize],
+) -> usize {
+    if candidates.len() <= 2 {
+        candidates[0]
+    } else {
+        strategy.choose(matrix, alphabet, candidates)
+    }
+}
+
+/// The strategies that can be selected by name. Strategies behind a feature are
+/// registered in `Registry::default` alongside the built-in ones, and scripted
+/// strategies are registered as they're named on the command line.
+pub struct Registry {
+    strategies: Vec<Box<dyn Strategy>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry { strategies: vec![] }
+    }
+
+    pub fn register(&mut self, strategy: impl Strategy + 'static) {
+        self.strategies.push(Box::new(strategy));
+    }
+
+    pub fn get(&self, name: &str) -> Result<&dyn Strategy> {
+        self.iter().find(|s| s.name() == name).ok_or_else(|| {
+            anyhow!(
+                "Unknown strategy \"{}\" (expected one of: {})",
+                name,
+                self.iter().map(|s| s.name()).join(", ")
+            )
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Strategy> {
+        self.strategies.iter().map(|s| s.as_ref())
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Registry::new();
+        registry.register(Frequency);
+        registry.register(Entropy);
+        registry.register(Minimax);
+        registry.register(Adaptive);
+        registry
+    }
+}
+
+pub struct Frequency;
+
+impl Strategy for Frequency {
+    fn name(&self) -> &str {
+        "frequency"
+    }
+
+    fn description(&self) -> &str {
+        "Guess the candidate whose letters are most common among the candidates"
+    }
+
+    fn choose(&self, matrix: &FeedbackMatrix, alphabet: &Alphabet, candidates: &[usize]) -> usize {
+        let freq = matrix.frequencies(alphabet, candidates);
+        let score = |&c: &usize| frequency_score(&matrix.guesses()[c], alphabet, &freq.letters);
+        // Ties go to the earliest word, matching the order of the suggestions
+        *candidates.iter().rev().max_by_key(|c| score(c)).unwrap()
+    }
+}
+
+pub struct Entropy;
+
+impl Strategy for Entropy {
+    fn name(&self) -> &str {
+        "entropy"
+    }
+
+    fn description(&self) -> &str {
+        "Guess the word that maximizes the expected information from its feedback"
+    }
+
+    fn choose(&self, matrix: &FeedbackMatrix, _: &Alphabet, candidates: &[usize]) -> usize {
+        best_guess(matrix, candidates, |sizes| {
+            // Maximizing the entropy is the same as minimizing the sum of s*log(s)
+            -sizes
+                .iter()
+                .filter(|&&s| s > 1)
+                .map(|&s| s as f64 * (s as f64).log2())
+                .sum::<f64>()
+        })
+    }
+}
+
+pub struct Minimax;
+
+impl Strategy for Minimax {
+    fn name(&self) -> &str {
+        "minimax"
+    }
+
+    fn description(&self) -> &str {
+        "Guess the word that minimizes the largest group of candidates left after its feedback"
+    }
+
+    fn choose(&self, matrix: &FeedbackMatrix, _: &Alphabet, candidates: &[usize]) -> usize {
+        best_guess(matrix, candidates, |sizes| {
+            minimax_score(sizes, candidates.len())
+        })
+    }
+}
+
+fn minimax_score(sizes: &[u32; PATTERNS], candidates: usize) -> f64 {
+    // Break ties on the worst case with the expected group size
+    let worst = *sizes.iter().max().unwrap() as f64;
+    let sum_squares: f64 = sizes.iter().map(|&s| (s as f64).powi(2)).sum();
+    -(worst * candidates.pow(2) as f64 + sum_squares)
+}
+
+/// The stage of a game `Adaptive` judges it to be at from the candidates left
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Opening,
+    Middle,
+    Endgame,
+}
+
+/// A strategy that changes with the phase of the game: covering common letters
+/// in the opening, getting the most information in the middle, and guessing the
+/// candidate with the best worst case at the end, since any guess then should be
+/// able to win
+pub struct Adaptive;
+
+impl Adaptive {
+    pub fn phase(matrix: &FeedbackMatrix, candidates: &[usize]) -> Phase {
+        if candidates.len() * OPENING_SHARE >= matrix.answers().len() {
+            Phase::Opening
+        } else if candidates.len() > ENDGAME_CANDIDATES {
+            Phase::Middle
+        } else {
+            Phase::Endgame
+        }
+    }
+}
+
+impl Strategy for Adaptive {
+    fn name(&self) -> &str {
+        "adaptive"
+    }
+
+    fn description(&self) -> &str {
+        "Guess by frequency in the opening, entropy in the middle, and minimax over the candidates at the end"
+    }
+
+    fn choose(&self, matrix: &FeedbackMatrix, alphabet: &Alphabet, candidates: &[usize]) -> usize {
+        match Adaptive::phase(matrix, candidates) {
+            Phase::Opening => Frequency.choose(matrix, alphabet, candidates),
+            Phase::Middle => Entropy.choose(matrix, alphabet, candidates),
+            // Ties go to the earliest candidate, like the other strategies
+            Phase::Endgame => *candidates
+                .iter()
+                .rev()
+                .max_by(|&&a, &&b| {
+                    let score = |guess| {
+                        minimax_score(
+                            &partition_sizes(matrix, guess, candidates),
+                            candidates.len(),
+                        )
+                    };
+                    score(a).total_cmp(&score(b))
+                })
+                .unwrap(),
+        }
+    }
+
+    fn explain(&self, matrix: &FeedbackMatrix, candidates: &[usize]) -> Option<String> {
+        Some(
+            match Adaptive::phase(matrix, candidates) {
+                Phase::Opening => "opening: the candidate with the most common letters",
+                Phase::Middle => "middle game: the guess with the most information",
+                Phase::Endgame => "endgame: the candidate with the smallest worst case",
+            }
+            .to_string(),
+        )
+    }
+}
+
+/// A strategy that falls back to the frequency strategy on turns it couldn't
+/// finish within a time budget, for keeping heavy strategies responsive on slow
+/// machines. How long a turn would take is judged by timing a sample of guesses.
+pub struct Budgeted<'a> {
+    strategy: &'a dyn Strategy,
+    budget: Duration,
+}
+
+impl<'a> Budgeted<'a> {
+    pub fn new(strategy: &'a dyn Strategy, budget: Duration) -> Self {
+        Budgeted { strategy, budget }
+    }
+}
+
+impl Strategy for Budgeted<'_> {
+    fn name(&self) -> &str {
+        self.strategy.name()
+    }
+
+    fn description(&self) -> &str {
+        self.strategy.description()
+    }
+
+    fn explain(&self, matrix: &FeedbackMatrix, candidates: &[usize]) -> Option<String> {
+        self.strategy.explain(matrix, candidates)
+    }
+
+    fn choose(&self, matrix: &FeedbackMatrix, alphabet: &Alphabet, candidates: &[usize]) -> usize {
+        let guesses = matrix.guesses().len();
+        let sample = BUDGET_SAMPLE.min(guesses);
+        let start = Instant::now();
+        for guess in 0..sample {
+            std::hint::black_box(partition_sizes(matrix, guess, candidates));
+        }
+        let estimate = start.elapsed().mul_f64(guesses as f64 / sample as f64);
+        if estimate > self.budget {
+            Frequency.choose(matrix, alphabet, candidates)
+        } else {
+            self.strategy.choose(matrix, alphabet, candidates)
+        }
+    }
+}
+
+/// Count how many candidates would be left under each feedback pattern of a guess
+pub fn partition_sizes(
+    matrix: &FeedbackMatrix,
+    guess: usize,
+    candidates: &[usize],
+) -> [u32; PATTERNS] {
+    let mut sizes = [0; PATTERNS];
+    for &candidate in candidates {
+        sizes[matrix.pattern(guess, candidate) as usize] += 1;
+    }
+    sizes
+}
+
+/// Find the guess with the highest score, preferring guesses that could be the answer
+fn best_guess(
+    matrix: &FeedbackMatrix,
+    candidates: &[usize],
+    score: impl Fn(&[u32; PATTERNS]) -> f64,
+) -> usize {
+    let mut is_candidate = vec![false; matrix.guesses().len()];
+    candidates.iter().for_each(|&c| is_candidate[c] = true);
+
+    let mut best = (f64::NEG_INFINITY, false, 0);
+    for (guess, &is_candidate) in is_candidate.iter().enumerate() {
+        let key = (
+            score(&partition_sizes(matrix, guess, candidates)),
+            is_candidate,
+        );
+        if key > (best.0, best.1) {
+            best = (key.0, key.1, guess);
+        }
+    }
+    best.2
+}
+
+/// How often each symbol of the alphabet appears in the words, indexed by the alphabet
+pub fn letter_frequencies(words: &[Word], alphabet: &Alphabet) -> Vec<i32> {
+    let count = |mut freq: Vec<i32>, word: &Word| {
+        word.0
+            .iter()
+            .filter_map(|&letter| alphabet.index(letter))
+            .for_each(|index| freq[index] += 1);
+        freq
+    };
+    if words.len() < PARALLEL_WORDS {
+        return words.iter().fold(vec![0; alphabet.len()], count);
+    }
+    // Each thread counts its share of the words, and the counts are added up
+    words
+        .par_iter()
+        .fold(|| vec![0; alphabet.len()], count)
+        .reduce(
+            || vec![0; alphabet.len()],
+            |mut a, b| {
+                a.iter_mut().zip(b).for_each(|(a, b)| *a += b);
+                a
+            },
+        )
+}
+
+/// How often each symbol appears in each position of the words, indexed by the alphabet
+pub fn position_frequencies(words: &[Word], alphabet: &Alphabet) -> [Vec<i32>; 5] {
+    let mut freq: [Vec<i32>; 5] = Default::default();
+    for (pos, freq) in freq.iter_mut().enumerate() {
+        *freq = vec![0; alphabet.len()];
+        words
+            .iter()
+            .filter_map(|word| alphabet.index(word.0[pos]))
+            .for_each(|index| freq[index] += 1);
+    }
+    freq
+}
+
+/// How often each symbol of an alphabet appears in some words, overall and in each
+/// position, indexed by the alphabet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frequencies {
+    pub letters: Vec<i32>,
+    pub positions: [Vec<i32>; 5],
+}
+
+impl Frequencies {
+    pub fn count(words: &[Word], alphabet: &Alphabet) -> Self {
+        Frequencies {
+            letters: letter_frequencies(words, alphabet),
+            positions: position_frequencies(words, alphabet),
+        }
+    }
+
+    /// Counts made when building, for a list built into the binary
+    pub fn from_counts(letters: &[i32], positions: [&[i32]; 5]) -> Self {
+        Frequencies {
+            letters: letters.to_vec(),
+            positions: positions.map(<[i32]>::to_vec),
+        }
+    }
+
+    /// The counts for both lists of words together
+    pub fn add(mut self, other: &Frequencies) -> Self {
+        let add = |total: &mut Vec<i32>, counts: &Vec<i32>| {
+            total.iter_mut().zip(counts).for_each(|(t, c)| *t += c)
+        };
+        add(&mut self.letters, &other.letters);
+        for (total, counts) in self.positions.iter_mut().zip(&other.positions) {
+            add(total, counts);
+        }
+        self
+    }
+}
+
+/// Score a word by how common its distinct letters are
+pub fn frequency_score(word: &Word, alphabet: &Alphabet, freq: &[i32]) -> i32 {
+    word.0
+        .iter()
+        .unique()
+        .filter_map(|&c| alphabet.index(c))
+        .map(|index| freq[index])
+        .sum()
+}
+
+#[test]
+fn test_letter_frequencies() {
+    let alphabet = Alphabet::letters();
+    let words: Vec<Word> = ["crane", "sissy", "humph"]
+        .iter()
+        .map(|&w| w.try_into().unwrap())
+        .collect();
+    let freq = letter_frequencies(&words, &alphabet);
+    assert_eq!(freq[alphabet.index('s').unwrap()], 3);
+    assert_eq!(freq[alphabet.index('h').unwrap()], 2);
+    // Counting across threads gives the same counts
+    let many: Vec<Word> = words
+        .iter()
+        .cycle()
+        .take(3 * PARALLEL_WORDS)
+        .copied()
+        .collect();
+    let expected: Vec<i32> = freq.iter().map(|f| f * PARALLEL_WORDS as i32).collect();
+    assert_eq!(letter_frequencies(&many, &alphabet), expected);
+}
+
+#[test]
+fn test_budgeted() {
+    use crate::bench::test_matrix;
+    let matrix = test_matrix();
+    let words = matrix.answers();
+    let alphabet = Alphabet::letters();
+    let candidates: Vec<usize> = (0..words.len()).collect();
+    let choose = |strategy: &dyn Strategy| strategy.choose(&matrix, &alphabet, &candidates);
+    let hour = Budgeted::new(&Minimax, Duration::from_secs(3600));
+    assert_eq!(choose(&hour), choose(&Minimax));
+    let none = Budgeted::new(&Minimax, Duration::ZERO);
+    assert_eq!(choose(&none), choose(&Frequency));
+}
+
+#[test]
+fn test_adaptive() {
+    use crate::bench::test_matrix;
+    let matrix = test_matrix();
+    let words = matrix.answers();
+    let alphabet = Alphabet::letters();
+    let all: Vec<usize> = (0..words.len()).collect();
+    assert_eq!(Adaptive::phase(&matrix, &all), Phase::Opening);
+    assert_eq!(
+        Adaptive.choose(&matrix, &alphabet, &all),
+        Frequency.choose(&matrix, &alphabet, &all)
+    );
+    assert!(Adaptive
+        .explain(&matrix, &all)
+        .unwrap()
+        .starts_with("opening"));
+    assert!(Frequency.explain(&matrix, &all).is_none());
+}